@@ -0,0 +1,445 @@
+//! Helpers for game clients on top of the [`GameClientCodec`](crate::GameClientCodec)
+
+use dpmaster_proto::messages::{
+    FilterExtOptions, GetServersExtResponseMessage, GetServersResponseMessage,
+};
+use futures::{Stream, StreamExt};
+use std::net::{SocketAddr, SocketAddrV4};
+use std::time::Duration;
+use tracing::warn;
+
+/// Result of a `getservers` or `getserversExt` query
+///
+/// Aggregates the `getserversResponse` or `getserversExtResponse` messages received for a query.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct QueryResult<A = SocketAddrV4> {
+    servers: Vec<A>,
+    responses: usize,
+    eot: bool,
+    elapsed: Duration,
+}
+
+impl QueryResult {
+    /// Creates a new `QueryResult` from the received `responses`, whether the query is complete and the `elapsed` time.
+    ///
+    /// Servers contained in multiple responses are only listed once, in the order they were first received.
+    pub fn new(responses: &[GetServersResponseMessage], eot: bool, elapsed: Duration) -> Self {
+        Self::from_servers(
+            responses.iter().flat_map(|response| response.servers()),
+            responses.len(),
+            eot,
+            elapsed,
+        )
+    }
+}
+
+impl QueryResult<SocketAddr> {
+    /// Like [`QueryResult::new`], but from the received `getserversExtResponse` messages
+    pub fn new_ext(
+        responses: &[GetServersExtResponseMessage],
+        eot: bool,
+        elapsed: Duration,
+    ) -> Self {
+        Self::from_servers(
+            responses.iter().flat_map(|response| response.servers()),
+            responses.len(),
+            eot,
+            elapsed,
+        )
+    }
+}
+
+impl<A: Copy + Eq + std::hash::Hash> QueryResult<A> {
+    fn from_servers<'a>(
+        servers: impl Iterator<Item = &'a A>,
+        responses: usize,
+        eot: bool,
+        elapsed: Duration,
+    ) -> Self
+    where
+        A: 'a,
+    {
+        let mut seen = std::collections::HashSet::new();
+        let servers = servers
+            .filter(|server| seen.insert(**server))
+            .copied()
+            .collect();
+
+        Self {
+            servers,
+            responses,
+            eot,
+            elapsed,
+        }
+    }
+}
+
+impl<A> QueryResult<A> {
+    /// Returns the deduplicated server socket addresses.
+    pub fn servers(&self) -> &[A] {
+        &self.servers[..]
+    }
+
+    /// Returns the number of response messages received.
+    pub fn responses(&self) -> usize {
+        self.responses
+    }
+
+    /// Returns whether a response with the EOT flag was received, i.e. the query is complete.
+    pub fn eot(&self) -> bool {
+        self.eot
+    }
+
+    /// Returns the time elapsed until the query was complete or timed out.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// Collects `getserversResponse` messages until one has the EOT flag set or the `timeout` elapses
+///
+/// Takes a stream of responses, e.g. a `UdpFramed` with a [`GameClientCodec`](crate::GameClientCodec).
+///
+/// Returns the responses collected so far and whether the query is complete, i.e. the last response has the EOT flag set.\
+/// Masters send the response messages via UDP, so the final message with the EOT flag might never arrive.
+/// In that case the responses collected until the `timeout` are still returned, but the query is incomplete.
+/// The same applies if the stream ends before a response with the EOT flag is received.
+///
+/// # Errors
+///
+/// Will return the first error of the stream, e.g. an invalid response.
+pub async fn collect_servers_until_eot<S>(
+    responses: S,
+    timeout: Duration,
+) -> Result<(Vec<GetServersResponseMessage>, bool), std::io::Error>
+where
+    S: Stream<Item = Result<(GetServersResponseMessage, SocketAddr), std::io::Error>> + Unpin,
+{
+    collect_until_eot(responses, timeout, |response, _addr| response.eot()).await
+}
+
+/// Collects responses until `inspect` returns `true` for one of them, i.e. it has the EOT flag set
+async fn collect_until_eot<S, T>(
+    mut responses: S,
+    timeout: Duration,
+    mut inspect: impl FnMut(&mut T, SocketAddr) -> bool,
+) -> Result<(Vec<T>, bool), std::io::Error>
+where
+    S: Stream<Item = Result<(T, SocketAddr), std::io::Error>> + Unpin,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut collected = Vec::new();
+
+    loop {
+        match tokio::time::timeout_at(deadline, responses.next()).await {
+            Err(_) | Ok(None) => return Ok((collected, false)),
+            Ok(Some(Err(e))) => return Err(e),
+            Ok(Some(Ok((mut response, addr)))) => {
+                let eot = inspect(&mut response, addr);
+                collected.push(response);
+                if eot {
+                    return Ok((collected, true));
+                }
+            }
+        }
+    }
+}
+
+/// Queries servers by collecting `getserversResponse` messages like [`collect_servers_until_eot`]
+///
+/// Returns a [`QueryResult`] aggregating the responses.
+///
+/// # Errors
+///
+/// Will return the first error of the stream, e.g. an invalid response.
+pub async fn query_servers<S>(
+    responses: S,
+    timeout: Duration,
+) -> Result<QueryResult, std::io::Error>
+where
+    S: Stream<Item = Result<(GetServersResponseMessage, SocketAddr), std::io::Error>> + Unpin,
+{
+    let start = tokio::time::Instant::now();
+    let (collected, eot) = collect_servers_until_eot(responses, timeout).await?;
+    Ok(QueryResult::new(&collected, eot, start.elapsed()))
+}
+
+/// Queries servers by collecting `getserversExtResponse` messages like [`collect_servers_until_eot`]
+///
+/// With the filter options of the `getserversExt` query as `requested`, servers of address families that were not requested
+/// are removed from the responses with a warning, see [`retain_requested_families`]. Otherwise all servers are kept.
+///
+/// Returns a [`QueryResult`] aggregating the responses.
+///
+/// # Errors
+///
+/// Will return the first error of the stream, e.g. an invalid response.
+pub async fn query_servers_ext<S>(
+    responses: S,
+    timeout: Duration,
+    requested: Option<&FilterExtOptions>,
+) -> Result<QueryResult<SocketAddr>, std::io::Error>
+where
+    S: Stream<Item = Result<(GetServersExtResponseMessage, SocketAddr), std::io::Error>> + Unpin,
+{
+    let start = tokio::time::Instant::now();
+    let (collected, eot) = collect_until_eot(responses, timeout, |response, addr| {
+        if let Some(requested) = requested {
+            let unexpected = retain_requested_families(response, requested);
+            if !unexpected.is_empty() {
+                warn!(
+                    master_server = %addr,
+                    ?unexpected,
+                    "Ignoring servers of address families not requested from master server"
+                );
+            }
+        }
+        response.eot()
+    })
+    .await?;
+    Ok(QueryResult::new_ext(&collected, eot, start.elapsed()))
+}
+
+/// Removes the servers of address families that were not requested by the `getserversExt` filter `options`
+///
+/// Masters should only respond with the requested families, but a buggy one might e.g. include IPv4 servers for an `ipv6`-only query.
+///
+/// Returns the removed servers, so the caller can warn about the unexpected response.
+pub fn retain_requested_families(
+    response: &mut GetServersExtResponseMessage,
+    options: &FilterExtOptions,
+) -> Vec<SocketAddr> {
+    let mut unexpected = Vec::new();
+    response.retain(|server| {
+        let accepted = options.accepts_family(server);
+        if !accepted {
+            unexpected.push(*server);
+        }
+        accepted
+    });
+    unexpected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+
+    fn response(
+        server: &str,
+        eot: bool,
+    ) -> Result<(GetServersResponseMessage, SocketAddr), std::io::Error> {
+        Ok((
+            GetServersResponseMessage::new(vec![server.parse().unwrap()], eot),
+            "192.0.2.255:27950".parse().unwrap(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_collect_servers_until_eot_complete() {
+        let responses = stream::iter(vec![
+            response("192.0.2.1:27960", false),
+            response("192.0.2.2:27960", true),
+            response("192.0.2.3:27960", false),
+        ]);
+
+        let (collected, complete) = collect_servers_until_eot(responses, Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(complete);
+        assert_eq!(collected.len(), 2);
+        assert!(collected[1].eot());
+    }
+
+    #[tokio::test]
+    async fn test_collect_servers_until_eot_timeout() {
+        let responses = stream::iter(vec![
+            response("192.0.2.1:27960", false),
+            response("192.0.2.2:27960", false),
+        ])
+        .chain(stream::pending());
+
+        let (collected, complete) = collect_servers_until_eot(responses, Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert!(!complete);
+        assert_eq!(
+            collected,
+            vec![
+                GetServersResponseMessage::new(vec!["192.0.2.1:27960".parse().unwrap()], false),
+                GetServersResponseMessage::new(vec!["192.0.2.2:27960".parse().unwrap()], false),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collect_servers_until_eot_error() {
+        let responses = stream::iter(vec![
+            response("192.0.2.1:27960", false),
+            Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "uhoh")),
+        ]);
+
+        let result = collect_servers_until_eot(responses, Duration::from_secs(1)).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_query_result() {
+        let responses = vec![
+            GetServersResponseMessage::new(
+                vec![
+                    "192.0.2.1:27960".parse().unwrap(),
+                    "192.0.2.2:27960".parse().unwrap(),
+                ],
+                false,
+            ),
+            GetServersResponseMessage::new(
+                vec![
+                    "192.0.2.2:27960".parse().unwrap(),
+                    "192.0.2.3:27960".parse().unwrap(),
+                ],
+                true,
+            ),
+        ];
+
+        let result = QueryResult::new(&responses, true, Duration::from_millis(42));
+        assert_eq!(
+            result.servers(),
+            &[
+                "192.0.2.1:27960".parse().unwrap(),
+                "192.0.2.2:27960".parse().unwrap(),
+                "192.0.2.3:27960".parse().unwrap(),
+            ][..]
+        );
+        assert_eq!(result.responses(), 2);
+        assert!(result.eot());
+        assert_eq!(result.elapsed(), Duration::from_millis(42));
+    }
+
+    #[test]
+    fn test_retain_requested_families_ipv6_only() {
+        let v4: SocketAddr = "192.0.2.1:27960".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8::1]:27960".parse().unwrap();
+        let mut response = GetServersExtResponseMessage::new(vec![v4, v6], true);
+        let options = FilterExtOptions::new(None, false, false, false, true);
+
+        let unexpected = retain_requested_families(&mut response, &options);
+        assert_eq!(unexpected, vec![v4]);
+        assert_eq!(response.servers(), &[v6]);
+    }
+
+    #[test]
+    fn test_retain_requested_families_both() {
+        let v4: SocketAddr = "192.0.2.1:27960".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8::1]:27960".parse().unwrap();
+        let mut response = GetServersExtResponseMessage::new(vec![v4, v6], true);
+        let options = FilterExtOptions::new(None, false, false, true, true);
+
+        assert!(retain_requested_families(&mut response, &options).is_empty());
+        assert_eq!(response.servers(), &[v4, v6]);
+    }
+
+    /// Runs `f` with a subscriber capturing the formatted log output
+    fn capture_logs<T>(f: impl FnOnce() -> T) -> (T, String) {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Captured(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Captured {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let result = tracing::subscriber::with_default(subscriber, f);
+
+        let output = captured.0.lock().unwrap().clone();
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    fn ext_responses(
+    ) -> impl Stream<Item = Result<(GetServersExtResponseMessage, SocketAddr), std::io::Error>> + Unpin
+    {
+        let v4: SocketAddr = "192.0.2.1:27960".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8::1]:27960".parse().unwrap();
+        let master: SocketAddr = "192.0.2.255:27950".parse().unwrap();
+        stream::iter(vec![
+            Ok((
+                GetServersExtResponseMessage::new(vec![v4, v6], false),
+                master,
+            )),
+            Ok((GetServersExtResponseMessage::new(vec![v6], true), master)),
+        ])
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_query_servers_ext_ipv6_only() {
+        let options = FilterExtOptions::new(None, false, false, false, true);
+        let (result, logs) = capture_logs(|| {
+            block_on(query_servers_ext(
+                ext_responses(),
+                Duration::from_secs(1),
+                Some(&options),
+            ))
+        });
+
+        let result = result.unwrap();
+        assert_eq!(
+            result.servers(),
+            &["[2001:db8::1]:27960".parse::<SocketAddr>().unwrap()]
+        );
+        assert_eq!(result.responses(), 2);
+        assert!(result.eot());
+        assert!(logs.contains("WARN"));
+        assert!(logs.contains("Ignoring servers of address families not requested"));
+        assert!(logs.contains("192.0.2.1:27960"));
+    }
+
+    #[test]
+    fn test_query_servers_ext_unfiltered() {
+        let (result, logs) = capture_logs(|| {
+            block_on(query_servers_ext(
+                ext_responses(),
+                Duration::from_secs(1),
+                None,
+            ))
+        });
+
+        assert_eq!(result.unwrap().servers().len(), 2);
+        assert_eq!(logs, "");
+    }
+
+    #[tokio::test]
+    async fn test_query_servers() {
+        let responses = stream::iter(vec![
+            response("192.0.2.1:27960", false),
+            response("192.0.2.1:27960", true),
+        ]);
+
+        let result = query_servers(responses, Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(result.servers(), &["192.0.2.1:27960".parse().unwrap()][..]);
+        assert_eq!(result.responses(), 2);
+        assert!(result.eot());
+    }
+}
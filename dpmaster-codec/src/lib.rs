@@ -1,29 +1,155 @@
+#[cfg(feature = "tokio")]
+pub mod client;
+#[cfg(feature = "tokio")]
+pub mod master;
+#[cfg(feature = "tokio")]
+pub mod stream;
+
+#[cfg(feature = "tokio")]
 use bytes::{BufMut, BytesMut};
 use cookie_factory::gen;
-use dpmaster_proto::deserializer::getserversresponse_message;
+use dpmaster_proto::deserializer::{getserversresponse_message, ParseError, VerboseError};
+use dpmaster_proto::error::DeserializationError;
 use dpmaster_proto::messages::{GetServersMessage, GetServersResponseMessage};
-use dpmaster_proto::serializer::gen_getservers_message;
+use dpmaster_proto::serializer::{gen_getservers_message, gen_getserversresponse_message};
+use nom::error::ContextError;
+#[cfg(feature = "tokio")]
+use std::marker::PhantomData;
+use thiserror::Error;
+#[cfg(feature = "tokio")]
 use tokio_util::codec::{Decoder, Encoder};
 
-pub struct GameClientCodec(());
+/// Parser error type used by a codec
+///
+/// The parser error types of [`dpmaster_proto`] borrow the input they failed on,
+/// so this is implemented for their `'static` variants which stand in for any input lifetime.
+pub trait ParseErrorType {
+    /// Parser error type for an input with lifetime `'a`
+    type Error<'a>: ParseError<&'a [u8]> + ContextError<&'a [u8]> + std::fmt::Debug;
+}
+
+impl ParseErrorType for DeserializationError<&'static [u8]> {
+    type Error<'a> = DeserializationError<&'a [u8]>;
+}
+
+impl ParseErrorType for VerboseError<&'static [u8]> {
+    type Error<'a> = VerboseError<&'a [u8]>;
+}
+
+impl ParseErrorType for () {
+    type Error<'a> = ();
+}
+
+/// Serializes a `getservers` message into `dst`
+///
+/// Runtime-agnostic, e.g. to send the datagram with any async runtime.
+///
+/// # Errors
+///
+/// Will return an error if writing to `dst` fails.
+pub fn encode<W: std::io::Write>(item: &GetServersMessage, dst: W) -> Result<(), std::io::Error> {
+    gen(gen_getservers_message(item), dst)
+        .map_err(gen_error_to_io)
+        .map(|_| ())
+}
+
+fn gen_error_to_io(e: cookie_factory::GenError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, e) // TODO
+}
+
+/// Default maximum size of an encoded message in bytes
+///
+/// Same as the maximum datagram size used by dpmaster, which stays below the common path MTU.
+pub const DEFAULT_MAX_DATAGRAM_LEN: usize = 1400;
+
+/// Error of a size-limited encode
+#[derive(Error, Debug)]
+pub enum CodecError {
+    /// The serialized message would not fit into a single datagram
+    #[error("message of {len} bytes exceeds the maximum of {max} bytes")]
+    MessageTooLarge { len: usize, max: usize },
+    /// Writing to the destination failed
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Serializes a `getserversResponse` message into `dst`, unless it exceeds `max_len` bytes
+///
+/// The size is checked upfront via [`serialized_len`](GetServersResponseMessage::serialized_len),
+/// so nothing is written for an oversized message. Split the servers across several messages instead.
+///
+/// # Errors
+///
+/// Will return [`CodecError::MessageTooLarge`] if the message exceeds `max_len`
+/// and [`CodecError::Io`] if writing to `dst` fails.
+pub fn encode_response<W: std::io::Write>(
+    item: &GetServersResponseMessage,
+    max_len: usize,
+    dst: W,
+) -> Result<(), CodecError> {
+    let len = item.serialized_len();
+    if len > max_len {
+        return Err(CodecError::MessageTooLarge { len, max: max_len });
+    }
+    gen(gen_getserversresponse_message(item), dst).map_err(gen_error_to_io)?;
+    Ok(())
+}
+
+/// Parses a `getserversResponse` message from a whole datagram `src`
+///
+/// Runtime-agnostic, e.g. to parse the datagram received with any async runtime.
+///
+/// # Errors
+///
+/// Will return an [`InvalidData`](std::io::ErrorKind::InvalidData) error if `src` is not a valid message.
+pub fn decode(src: &[u8]) -> Result<GetServersResponseMessage, std::io::Error> {
+    decode_with_error_type::<DeserializationError<&'static [u8]>>(src)
+}
+
+/// Like [`decode`], but with the parser error type `E`
+pub fn decode_with_error_type<E: ParseErrorType>(
+    src: &[u8],
+) -> Result<GetServersResponseMessage, std::io::Error> {
+    getserversresponse_message::<E::Error<'_>>(src)
+        .map(|(_i, msg)| msg)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{:?}", e)))
+}
+
+/// Codec for game clients, i.e. `getservers` requests and `getserversResponse` responses
+///
+/// The parser error type `E` determines how much detail decoding errors contain,
+/// e.g. [`VerboseError`] for diagnostics or `()` for speed.
+///
+/// Thin adapter for [`encode`] and [`decode`].
+#[cfg(feature = "tokio")]
+pub struct GameClientCodec<E = DeserializationError<&'static [u8]>>(PhantomData<E>);
 
+#[cfg(feature = "tokio")]
 impl GameClientCodec {
     pub fn new() -> Self {
-        Self(())
+        Self::with_error_type()
     }
 }
 
-impl Encoder<GetServersMessage> for GameClientCodec {
+#[cfg(feature = "tokio")]
+impl<E: ParseErrorType> GameClientCodec<E> {
+    /// Creates a new `GameClientCodec` with the parser error type `E`.
+    pub fn with_error_type() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<E> Encoder<GetServersMessage> for GameClientCodec<E> {
     type Error = std::io::Error;
 
     fn encode(&mut self, item: GetServersMessage, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        gen(gen_getservers_message(&item), dst.writer())
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e)) // TODO
-            .map(|_| ())
+        encode(&item, dst.writer())
     }
 }
 
-impl Decoder for GameClientCodec {
+#[cfg(feature = "tokio")]
+impl<E: ParseErrorType> Decoder for GameClientCodec<E> {
     type Item = GetServersResponseMessage;
     type Error = std::io::Error;
 
@@ -31,23 +157,176 @@ impl Decoder for GameClientCodec {
         if src.is_empty() {
             Ok(None)
         } else {
-            let msg = getserversresponse_message(&src[..]);
-            match msg {
-                Err(_e) => Err(std::io::Error::new(std::io::ErrorKind::Other, "uhoh")), // TODO
-                Ok((_i, msg)) => {
-                    // the parser operates on whole packets, so we can assume it parsed one on success
-                    src.clear();
-                    Ok(Some(msg))
-                }
-            }
+            let msg = decode_with_error_type::<E>(&src[..])?;
+            // the parser operates on whole packets, so we can assume it parsed one on success
+            src.clear();
+            Ok(Some(msg))
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    const RESPONSE: &[u8] =
+        b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0";
+
+    #[cfg(feature = "tokio")]
+    fn decode_codec<E: ParseErrorType>(
+        mut codec: GameClientCodec<E>,
+        data: &[u8],
+    ) -> Result<Option<GetServersResponseMessage>, std::io::Error> {
+        let mut src = BytesMut::from(data);
+        codec.decode(&mut src)
+    }
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_decode_deserialization_error() {
+        let codec = GameClientCodec::new();
+        let msg = decode_codec(codec, RESPONSE).unwrap().unwrap();
+        assert_eq!(
+            msg,
+            GetServersResponseMessage::new(vec!["1.2.3.4:2048".parse().unwrap()], true)
+        );
+
+        let codec = GameClientCodec::new();
+        assert!(decode_codec(codec, b"hurz").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_decode_verbose_error() {
+        let codec = GameClientCodec::<VerboseError<&'static [u8]>>::with_error_type();
+        assert!(decode_codec(codec, RESPONSE).unwrap().is_some());
+
+        let codec = GameClientCodec::<VerboseError<&'static [u8]>>::with_error_type();
+        let err = decode_codec(codec, b"hurz").unwrap_err();
+        assert!(err.to_string().contains("MessagePrefix"));
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_decode_unit_error() {
+        let codec = GameClientCodec::<()>::with_error_type();
+        assert!(decode_codec(codec, RESPONSE).unwrap().is_some());
+
+        let codec = GameClientCodec::<()>::with_error_type();
+        assert!(decode_codec(codec, b"hurz").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_codec_encode() {
+        let mut codec = GameClientCodec::new();
+        let getservers = GetServersMessage::new(
+            Some("Nexuiz".parse().unwrap()),
+            dpmaster_proto::messages::ProtocolNumber::new(3).unwrap(),
+            dpmaster_proto::messages::FilterOptions::new(Some("ctf".parse().unwrap()), true, true),
+        );
+        let mut dst = BytesMut::new();
+        codec.encode(getservers, &mut dst).unwrap();
+        assert_eq!(
+            &dst[..],
+            &b"\xFF\xFF\xFF\xFFgetservers Nexuiz 3 gametype=ctf empty full"[..]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_codec_decode() {
+        let mut codec = GameClientCodec::new();
+        let mut src = BytesMut::from(RESPONSE);
+        let msg = codec.decode(&mut src).unwrap();
+        assert_eq!(
+            msg,
+            Some(GetServersResponseMessage::new(
+                vec!["1.2.3.4:2048".parse().unwrap()],
+                true
+            ))
+        );
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_codec_decode_empty() {
+        let mut codec = GameClientCodec::new();
+        let mut src = BytesMut::new();
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_codec_decode_malformed() {
+        let mut codec = GameClientCodec::new();
+        let mut src = BytesMut::from(&b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02"[..]);
+        let err = codec.decode(&mut src).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_encode() {
+        let getservers = GetServersMessage::new(
+            None,
+            dpmaster_proto::messages::ProtocolNumber::new(68).unwrap(),
+            dpmaster_proto::messages::FilterOptions::new(None, true, false),
+        );
+        let mut dst = Vec::new();
+        encode(&getservers, &mut dst).unwrap();
+        assert_eq!(dst, b"\xFF\xFF\xFF\xFFgetservers 68 empty");
+    }
+
+    #[test]
+    fn test_decode() {
+        let msg = decode(RESPONSE).unwrap();
+        assert_eq!(
+            msg,
+            GetServersResponseMessage::new(vec!["1.2.3.4:2048".parse().unwrap()], true)
+        );
+
+        let err = decode(b"hurz").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_encode_response() {
+        let message = GetServersResponseMessage::new(vec!["1.2.3.4:2048".parse().unwrap()], true);
+        let mut dst = Vec::new();
+        encode_response(&message, DEFAULT_MAX_DATAGRAM_LEN, &mut dst).unwrap();
+        assert_eq!(dst, RESPONSE);
+
+        let mut dst = Vec::new();
+        encode_response(&message, RESPONSE.len(), &mut dst).unwrap();
+        assert_eq!(dst, RESPONSE);
+    }
+
+    #[test]
+    fn test_encode_response_too_large() {
+        let servers = (0..=200)
+            .map(|i| std::net::SocketAddrV4::new([192, 0, 2, i as u8].into(), 27960))
+            .collect();
+        let message = GetServersResponseMessage::new(servers, true);
+        let mut dst = Vec::new();
+        let err = encode_response(&message, DEFAULT_MAX_DATAGRAM_LEN, &mut dst).unwrap_err();
+        assert!(matches!(
+            err,
+            CodecError::MessageTooLarge { len, max: DEFAULT_MAX_DATAGRAM_LEN } if len == message.serialized_len()
+        ));
+        assert!(dst.is_empty());
+    }
+
+    #[test]
+    fn test_decode_with_error_type() {
+        assert!(decode_with_error_type::<()>(RESPONSE).is_ok());
+
+        let err = decode_with_error_type::<VerboseError<&'static [u8]>>(b"hurz").unwrap_err();
+        assert!(err.to_string().contains("MessagePrefix"));
+    }
 }
@@ -0,0 +1,79 @@
+//! Helpers for master servers, the counterpart to the [game client helpers](crate::client)
+
+use crate::{encode_response, CodecError};
+use dpmaster_proto::messages::GetServersResponseMessage;
+use std::net::{SocketAddr, SocketAddrV4};
+use tokio::net::UdpSocket;
+
+/// Sends `servers` to `addr` as `getserversResponse` messages of at most `max_len` bytes each
+///
+/// Large server lists are split into multiple datagrams with [`GetServersResponseMessage::paginate`],
+/// only the last one has the EOT flag set.
+///
+/// Returns the number of datagrams sent.
+///
+/// # Errors
+///
+/// Will return [`CodecError::MessageTooLarge`] if `max_len` is too small for even a single server
+/// and [`CodecError::Io`] if sending fails. Datagrams sent before the error are not retracted.
+pub async fn send_getservers_response(
+    socket: &UdpSocket,
+    addr: SocketAddr,
+    servers: &[SocketAddrV4],
+    max_len: usize,
+) -> Result<usize, CodecError> {
+    let mut buf = Vec::with_capacity(max_len);
+    let mut sent = 0;
+    for message in GetServersResponseMessage::paginate(servers, max_len) {
+        buf.clear();
+        encode_response(&message, max_len, &mut buf)?;
+        socket.send_to(&buf, addr).await?;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode;
+
+    #[tokio::test]
+    async fn test_send_getservers_response() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let receiver_addr = receiver.local_addr().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let servers: Vec<SocketAddrV4> = (0..=255)
+            .map(|i| SocketAddrV4::new([192, 0, 2, i].into(), 27960))
+            .collect();
+        let sent = send_getservers_response(&sender, receiver_addr, &servers, 1400)
+            .await
+            .unwrap();
+        assert_eq!(sent, 2);
+
+        let mut received = Vec::new();
+        let mut buf = vec![0; 1400];
+        loop {
+            let len = receiver.recv(&mut buf).await.unwrap();
+            let response = decode(&buf[..len]).unwrap();
+            received.extend_from_slice(response.servers());
+            if response.eot() {
+                break;
+            }
+        }
+        assert_eq!(received, servers);
+    }
+
+    #[tokio::test]
+    async fn test_send_getservers_response_too_small() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let servers = vec!["192.0.2.1:27960".parse().unwrap()];
+        let err = send_getservers_response(&sender, receiver.local_addr().unwrap(), &servers, 16)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CodecError::MessageTooLarge { max: 16, .. }));
+    }
+}
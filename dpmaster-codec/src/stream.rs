@@ -0,0 +1,108 @@
+//! Stream of any message received on a UDP socket, see [`MessageStream`]
+
+use dpmaster_proto::messages::Message;
+use futures::{ready, Stream};
+use std::convert::TryFrom;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::ReadBuf;
+use tokio::net::UdpSocket;
+
+/// Maximum payload size of a UDP datagram
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Stream of messages received on a [`UdpSocket`]
+///
+/// Unlike a `UdpFramed` with a role-specific codec like the [`GameClientCodec`](crate::GameClientCodec),
+/// this yields any [`Message`] together with the socket address it was received from, e.g. for a master server.\
+/// Each datagram is parsed as a whole message.
+///
+/// Invalid datagrams are yielded as [`InvalidData`](std::io::ErrorKind::InvalidData) errors,
+/// but the stream continues with the next datagram, so a single malformed datagram does not end it.
+/// Errors of the socket itself are yielded as-is.
+#[derive(Debug)]
+pub struct MessageStream {
+    socket: UdpSocket,
+    buf: Vec<u8>,
+}
+
+impl MessageStream {
+    /// Creates a new `MessageStream` receiving on `socket`.
+    pub fn new(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            buf: vec![0; MAX_DATAGRAM_SIZE],
+        }
+    }
+
+    /// Returns the socket, e.g. to send responses.
+    pub fn get_ref(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Consumes this stream and returns the socket.
+    pub fn into_inner(self) -> UdpSocket {
+        self.socket
+    }
+}
+
+impl Stream for MessageStream {
+    type Item = Result<(Message, SocketAddr), std::io::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut buf = ReadBuf::new(&mut this.buf);
+        let addr = match ready!(this.socket.poll_recv_from(cx, &mut buf)) {
+            Ok(addr) => addr,
+            Err(e) => return Poll::Ready(Some(Err(e))),
+        };
+
+        let item = Message::try_from(buf.filled())
+            .map(|message| (message, addr))
+            .map_err(std::io::Error::from);
+        Poll::Ready(Some(item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dpmaster_proto::messages::{HeartbeatMessage, ProtocolName};
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_message_stream() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let receiver_addr = receiver.local_addr().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sender_addr = sender.local_addr().unwrap();
+        let mut stream = MessageStream::new(receiver);
+
+        sender
+            .send_to(b"\xFF\xFF\xFF\xFFheartbeat DarkPlaces\n", receiver_addr)
+            .await
+            .unwrap();
+        let (message, addr) = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            message,
+            Message::Heartbeat(HeartbeatMessage::new(ProtocolName::default()))
+        );
+        assert_eq!(addr, sender_addr);
+
+        sender.send_to(b"hurz", receiver_addr).await.unwrap();
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        // continues after an invalid datagram
+        sender
+            .send_to(b"\xFF\xFF\xFF\xFFgetservers 68 empty", receiver_addr)
+            .await
+            .unwrap();
+        let (message, _) = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            message.command(),
+            dpmaster_proto::messages::Command::GetServers
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! End-to-end `heartbeat` message flow between a game server and a master server over loopback UDP
+
+#![cfg(feature = "tokio")]
+
+use cookie_factory::gen_simple;
+use dpmaster_codec::stream::MessageStream;
+use dpmaster_proto::flow::ChallengeFlow;
+use dpmaster_proto::messages::{
+    Challenge, HeartbeatMessage, Info, InfoKey, InfoResponseMessage, InfoValue, Message,
+    ProtocolName,
+};
+use dpmaster_proto::serializer::{
+    gen_getinfo_message, gen_heartbeat_message, gen_inforesponse_message,
+};
+use futures::StreamExt;
+use tokio::net::UdpSocket;
+
+async fn bind() -> MessageStream {
+    MessageStream::new(UdpSocket::bind("127.0.0.1:0").await.unwrap())
+}
+
+#[tokio::test]
+async fn test_heartbeat_flow() {
+    let mut master = bind().await;
+    let master_addr = master.get_ref().local_addr().unwrap();
+    let mut game_server = bind().await;
+
+    // ❶ game server announces itself
+    let heartbeat = HeartbeatMessage::new(ProtocolName::default());
+    let datagram = gen_simple(gen_heartbeat_message(&heartbeat), Vec::new()).unwrap();
+    game_server
+        .get_ref()
+        .send_to(&datagram, master_addr)
+        .await
+        .unwrap();
+
+    // ❷ master challenges the game server
+    let (message, game_server_addr) = master.next().await.unwrap().unwrap();
+    let heartbeat = match message {
+        Message::Heartbeat(heartbeat) => heartbeat,
+        message => panic!("unexpected message {:?}", message),
+    };
+    let (flow, getinfo) =
+        ChallengeFlow::start(&heartbeat, Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap());
+    let datagram = gen_simple(gen_getinfo_message(&getinfo), Vec::new()).unwrap();
+    master
+        .get_ref()
+        .send_to(&datagram, game_server_addr)
+        .await
+        .unwrap();
+
+    // ❸ game server responds with its info, echoing the challenge
+    let (message, addr) = game_server.next().await.unwrap().unwrap();
+    assert_eq!(addr, master_addr);
+    let getinfo = match message {
+        Message::GetInfo(getinfo) => getinfo,
+        message => panic!("unexpected message {:?}", message),
+    };
+    let mut info = Info::new();
+    info.insert(
+        InfoKey::new(b"challenge".to_vec()).unwrap(),
+        InfoValue::new(getinfo.challenge()[..].to_vec()).unwrap(),
+    );
+    info.insert(
+        InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
+        InfoValue::new(b"8".to_vec()).unwrap(),
+    );
+    let inforesponse = InfoResponseMessage::new(info);
+    let datagram = gen_simple(gen_inforesponse_message(&inforesponse), Vec::new()).unwrap();
+    game_server
+        .get_ref()
+        .send_to(&datagram, master_addr)
+        .await
+        .unwrap();
+
+    // ❹ master authenticates the info
+    let (message, addr) = master.next().await.unwrap().unwrap();
+    assert_eq!(addr, game_server_addr);
+    let inforesponse = match message {
+        Message::InfoResponse(inforesponse) => inforesponse,
+        message => panic!("unexpected message {:?}", message),
+    };
+    let info = flow.verify(&inforesponse).unwrap();
+    assert_eq!(info, inforesponse.info());
+}
@@ -1,14 +1,17 @@
 use clap::Parser as _;
 use color_eyre::{eyre::Report, eyre::WrapErr};
+use dpmaster_codec::client::{query_servers, QueryResult};
 use dpmaster_codec::GameClientCodec;
-use dpmaster_proto::messages::{FilterOptions, GameName, GameType, GetServersMessage};
+use dpmaster_proto::messages::{
+    FilterOptions, GameName, GameType, GetServersMessage, ProtocolNumber,
+};
 use eyre::eyre;
 use futures::SinkExt;
 use std::net::ToSocketAddrs;
+use std::time::Duration;
 use tokio::net::UdpSocket;
-use tokio_stream::StreamExt;
 use tokio_util::udp::UdpFramed;
-use tracing::{debug, info};
+use tracing::{debug, info, trace, warn};
 
 /// Query dpmaster servers like a game client
 #[derive(clap::Parser, Debug)]
@@ -36,7 +39,7 @@ struct GetServersOpts {
 
     /// Protocol version to query for, e.g. `68`
     #[arg(short, long)]
-    protocol_number: u32,
+    protocol_number: ProtocolNumber,
 
     /// Game type to query for, e.g. `4` for CTF in Q3A
     #[arg(short = 't', long)]
@@ -49,15 +52,33 @@ struct GetServersOpts {
     /// Ask for full servers in query
     #[arg(short, long)]
     full: bool,
+
+    /// Seconds to wait for the complete response, e.g. `5`
+    #[arg(long, default_value_t = 5)]
+    timeout: u64,
+
+    /// Log each server address individually at trace level, e.g. with `RUST_LOG=trace`
+    #[arg(long)]
+    verbose_servers: bool,
+
+    /// Suppress logging, so that only the server addresses are printed, e.g. to pipe them into other tools
+    ///
+    /// Logs anyway if `RUST_LOG` is set.
+    #[arg(long)]
+    plain: bool,
 }
 
 #[tokio::main]
 pub async fn main() -> Result<(), Report> {
-    install_tracing();
+    let opts: Opts = Opts::parse();
+
+    let quiet = match &opts.subcmd {
+        SubCommand::GetServers(getservers_opts) => getservers_opts.plain,
+    };
+    install_tracing(quiet);
 
     color_eyre::install()?;
 
-    let opts: Opts = Opts::parse();
     debug!(?opts, "Parsed CLI options");
 
     match opts.subcmd {
@@ -100,23 +121,55 @@ pub async fn main() -> Result<(), Report> {
             info!(request = ? getservers, "Sending request");
             framed.send((getservers, addr)).await?;
 
-            while let Some((getserversresponse, _addr)) = framed
-                .try_next()
+            let result = query_servers(&mut framed, Duration::from_secs(getservers_opts.timeout))
                 .await
-                .wrap_err("Could not recieve message from master server")?
-            {
-                info!(response = ? getserversresponse, "Recieved message from master server");
-                if getserversresponse.eot() {
-                    break;
-                }
+                .wrap_err("Could not recieve message from master server")?;
+            log_servers(&result, getservers_opts.verbose_servers);
+            if !result.eot() {
+                warn!(
+                    timeout = getservers_opts.timeout,
+                    "Did not recieve complete response from master server"
+                );
             }
+            write_servers(&result, &mut std::io::stdout().lock())?;
         }
     }
 
     Ok(())
 }
 
-fn install_tracing() {
+/// Logs a summary of the query `result`, and each server address at trace level with `verbose_servers`
+fn log_servers(result: &QueryResult, verbose_servers: bool) {
+    info!(
+        servers = result.servers().len(),
+        responses = result.responses(),
+        elapsed = ? result.elapsed(),
+        "Recieved response from master server"
+    );
+    if verbose_servers {
+        for server in result.servers() {
+            trace!(server = % server, "Server in response");
+        }
+    }
+}
+
+/// Writes the server addresses of the query `result`, one `ip:port` per line
+fn write_servers(result: &QueryResult, out: &mut impl std::io::Write) -> std::io::Result<()> {
+    let mut plain = String::new();
+    for server in result.servers() {
+        plain.push_str(&server.to_string());
+        plain.push('\n');
+    }
+    out.write_all(plain.as_bytes())?;
+    out.flush()
+}
+
+/// Logs to stderr, filtered by `RUST_LOG` or at info level by default, or not at all by default when `quiet`
+fn install_tracing(quiet: bool) {
+    if quiet && std::env::var_os("RUST_LOG").is_none() {
+        return;
+    }
+
     use tracing_error::ErrorLayer;
     use tracing_subscriber::prelude::*;
     use tracing_subscriber::{fmt, EnvFilter};
@@ -135,3 +188,77 @@ fn install_tracing() {
         .with(ErrorLayer::default())
         .init();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dpmaster_proto::messages::GetServersResponseMessage;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture_log_servers(verbose_servers: bool) -> String {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        let response = GetServersResponseMessage::new(
+            vec![
+                "192.0.2.1:27960".parse().unwrap(),
+                "192.0.2.2:27960".parse().unwrap(),
+            ],
+            true,
+        );
+        let result = QueryResult::new(&[response], true, Duration::from_millis(42));
+        tracing::subscriber::with_default(subscriber, || log_servers(&result, verbose_servers));
+
+        let output = captured.0.lock().unwrap().clone();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_write_servers() {
+        let response = GetServersResponseMessage::new(
+            vec![
+                "192.0.2.1:27960".parse().unwrap(),
+                "198.51.100.2:27961".parse().unwrap(),
+            ],
+            true,
+        );
+        let result = QueryResult::new(&[response], true, Duration::from_millis(42));
+
+        let mut out = Vec::new();
+        write_servers(&result, &mut out).unwrap();
+        assert_eq!(out, b"192.0.2.1:27960\n198.51.100.2:27961\n");
+    }
+
+    #[test]
+    fn test_log_servers_summary() {
+        let output = capture_log_servers(false);
+        assert!(output.contains("servers=2"));
+        assert!(!output.contains("192.0.2.1:27960"));
+    }
+
+    #[test]
+    fn test_log_servers_verbose() {
+        let output = capture_log_servers(true);
+        assert!(output.contains("servers=2"));
+        assert!(output.contains("server=192.0.2.1:27960"));
+        assert!(output.contains("server=192.0.2.2:27960"));
+    }
+}
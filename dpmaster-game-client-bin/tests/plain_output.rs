@@ -0,0 +1,47 @@
+use std::net::UdpSocket;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+/// Answers a single `getservers` query with a canned `getserversResponse`
+fn canned_master_server() -> (UdpSocket, thread::JoinHandle<()>) {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    // don't hang the test if the client never sends its query
+    socket
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    let master = socket.try_clone().unwrap();
+    let handle = thread::spawn(move || {
+        let mut buf = [0; 1400];
+        let (len, client) = master.recv_from(&mut buf).unwrap();
+        assert!(buf[..len].starts_with(b"\xFF\xFF\xFF\xFFgetservers 68"));
+
+        let response = b"\xFF\xFF\xFF\xFFgetserversResponse\
+            \\\xC0\x00\x02\x01\x6D\x38\
+            \\\xC6\x33\x64\x02\x6D\x39\
+            \\EOT\0\0\0";
+        master.send_to(&response[..], client).unwrap();
+    });
+    (socket, handle)
+}
+
+#[test]
+fn test_plain_output() {
+    let (master, handle) = canned_master_server();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dpmaster-game-client-bin"))
+        .args(["get-servers", "--master-server"])
+        .arg(master.local_addr().unwrap().to_string())
+        .args(["--protocol-number", "68", "--plain"])
+        .env_remove("RUST_LOG")
+        .output()
+        .unwrap();
+    handle.join().unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "192.0.2.1:27960\n198.51.100.2:27961\n"
+    );
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+}
@@ -0,0 +1,82 @@
+//! Worst-case `infoResponse` parsing, i.e. many tiny key-value pairs
+//!
+//! Contrasts the parser, which pre-sizes the `Info` with a capacity hint, with growing the `Info` from empty,
+//! by time as well as by the number of (re)allocations.
+//!
+//! Run with `cargo bench -p dpmaster-proto --bench info`.
+
+use dpmaster_proto::deserializer::{inforesponse_message, inforesponse_message_unhinted};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 1000;
+
+/// Counts allocations and reallocations
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// `infoResponse` message with `pairs` key-value pairs of one byte each
+fn tiny_pairs(pairs: usize) -> Vec<u8> {
+    let mut data = b"\xFF\xFF\xFF\xFFinfoResponse\n".to_vec();
+    for i in 0..pairs {
+        data.extend_from_slice(format!("\\{}\\b", i).as_bytes());
+    }
+    data
+}
+
+/// Runs `f` `ITERATIONS` times and prints the average time and number of (re)allocations
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    // warm up
+    black_box(f());
+
+    let mut elapsed = Duration::default();
+    let mut allocations = 0;
+    for _ in 0..ITERATIONS {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        let output = f();
+        elapsed += start.elapsed();
+        allocations += ALLOCATIONS.load(Ordering::Relaxed) - before;
+        black_box(output);
+    }
+    println!(
+        "{:<36} {:>12?}/iter {:>8} allocs/iter",
+        name,
+        elapsed / ITERATIONS,
+        allocations / ITERATIONS as usize
+    );
+}
+
+fn main() {
+    for &pairs in &[10, 100, 1000] {
+        let data = tiny_pairs(pairs);
+        bench(&format!("inforesponse {} pairs, hinted", pairs), || {
+            inforesponse_message(black_box(&data)).unwrap()
+        });
+        bench(&format!("inforesponse {} pairs, unhinted", pairs), || {
+            inforesponse_message_unhinted(black_box(&data)).unwrap()
+        });
+    }
+}
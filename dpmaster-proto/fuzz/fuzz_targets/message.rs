@@ -0,0 +1,11 @@
+#![no_main]
+use dpmaster_proto::deserializer::{parse_message, parse_message_verbose};
+use libfuzzer_sys::fuzz_target;
+
+// ```
+// dpmaster-proto$ cargo fuzz run message -- -dict=fuzz/dictionaries/message -max_len=1500 -timeout=1
+// ```
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_message(data);
+    let _ = parse_message_verbose(data);
+});
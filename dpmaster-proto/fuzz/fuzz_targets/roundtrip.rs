@@ -0,0 +1,123 @@
+#![no_main]
+use cookie_factory::gen_simple;
+use dpmaster_proto::deserializer::parse_message;
+use dpmaster_proto::messages::{
+    Challenge, FilterExtOptions, FilterOptions, GameName, GameType, GetInfoMessage,
+    GetServersExtMessage, GetServersMessage, GetServersResponseMessage, HeartbeatMessage, Info,
+    InfoKey, InfoResponseMessage, InfoValue, Message, PrintMessage, ProtocolName, ProtocolNumber,
+};
+use dpmaster_proto::serializer::gen_message;
+use libfuzzer_sys::arbitrary::{Error, Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use std::net::SocketAddrV4;
+
+// Generates a structured `Message`, serializes it and checks that parsing reproduces it exactly.
+// Unlike the byte-input targets this reaches asymmetries between the serializer and the parsers.
+//
+// ```
+// dpmaster-proto$ cargo fuzz run roundtrip -- -max_len=1500 -timeout=1
+// ```
+fuzz_target!(|data: &[u8]| {
+    let message = match message(&mut Unstructured::new(data)) {
+        Ok(message) => message,
+        Err(_) => return,
+    };
+
+    let buffer = gen_simple(gen_message(&message), Vec::new()).expect("serializing to a Vec");
+    let (rest, parsed) = parse_message(&buffer)
+        .unwrap_or_else(|e| panic!("failed to parse serialized {:?}: {:?}", message, e));
+    assert!(rest.is_empty(), "trailing bytes after {:?}", message);
+    assert_eq!(parsed, message);
+});
+
+/// Printable ASCII, which the validating constructors narrow down further
+const PRINTABLE: std::ops::RangeInclusive<u8> = 33..=126;
+
+fn bytes(u: &mut Unstructured) -> Result<Vec<u8>> {
+    let len = u.int_in_range(1..=16)?;
+    (0..len).map(|_| u.int_in_range(PRINTABLE)).collect()
+}
+
+/// Like [`bytes`], but without digits, which end a game name in `getservers` and `getserversExt` messages
+fn game_name(u: &mut Unstructured) -> Result<GameName> {
+    let mut bytes = bytes(u)?;
+    bytes.retain(|byte| !byte.is_ascii_digit());
+    if bytes.is_empty() {
+        return Err(Error::IncorrectFormat);
+    }
+    valid(GameName::new(bytes))
+}
+
+fn valid<T, E>(result: std::result::Result<T, E>) -> Result<T> {
+    result.map_err(|_| Error::IncorrectFormat)
+}
+
+fn protocol_number(u: &mut Unstructured) -> Result<ProtocolNumber> {
+    ProtocolNumber::new(u.arbitrary()?).ok_or(Error::IncorrectFormat)
+}
+
+fn game_type(u: &mut Unstructured) -> Result<Option<GameType>> {
+    if u.arbitrary()? {
+        Ok(Some(valid(GameType::new(bytes(u)?))?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn info(u: &mut Unstructured) -> Result<Info> {
+    // the parser requires at least one key-value pair
+    let mut info = Info::new();
+    for _ in 0..u.int_in_range(1..=8)? {
+        info.insert(valid(InfoKey::new(bytes(u)?))?, valid(InfoValue::new(bytes(u)?))?);
+    }
+    Ok(info)
+}
+
+/// Any server address but `69.79.84.0:0`, which is serialized as the `\EOT\0\0\0` marker
+fn server(u: &mut Unstructured) -> Result<SocketAddrV4> {
+    let server = SocketAddrV4::new(u.arbitrary::<u32>()?.into(), u.arbitrary()?);
+    if server == SocketAddrV4::new([69, 79, 84, 0].into(), 0) {
+        return Err(Error::IncorrectFormat);
+    }
+    Ok(server)
+}
+
+fn message(u: &mut Unstructured) -> Result<Message> {
+    Ok(match u.int_in_range(0..=6)? {
+        0 => Message::Heartbeat(HeartbeatMessage::new(valid(ProtocolName::new(bytes(u)?))?)),
+        1 => Message::GetInfo(GetInfoMessage::new(valid(Challenge::new(bytes(u)?))?)),
+        2 => Message::InfoResponse(InfoResponseMessage::new(info(u)?)),
+        3 => {
+            let game_name = if u.arbitrary()? {
+                Some(game_name(u)?)
+            } else {
+                None
+            };
+            Message::GetServers(GetServersMessage::new(
+                game_name,
+                protocol_number(u)?,
+                FilterOptions::new(game_type(u)?, u.arbitrary()?, u.arbitrary()?),
+            ))
+        }
+        4 => {
+            let servers: Vec<_> = (0..u.int_in_range(0..=32)?)
+                .map(|_| server(u))
+                .collect::<Result<_>>()?;
+            // Strict mode rejects a response with neither servers nor EOT as truncated
+            let eot = servers.is_empty() || u.arbitrary()?;
+            Message::GetServersResponse(GetServersResponseMessage::new(servers, eot))
+        }
+        5 => Message::GetServersExt(GetServersExtMessage::new(
+            game_name(u)?,
+            protocol_number(u)?,
+            FilterExtOptions::new(
+                game_type(u)?,
+                u.arbitrary()?,
+                u.arbitrary()?,
+                u.arbitrary()?,
+                u.arbitrary()?,
+            ),
+        )),
+        _ => Message::Print(PrintMessage::new(bytes(u)?)),
+    })
+}
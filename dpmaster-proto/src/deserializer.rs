@@ -1,45 +1,198 @@
 //! deserializer for messages
 
+pub mod whitespace;
+
 use crate::error::DeserializationError;
 use crate::messages::{
-    Challenge, FilterOptions, GameName, GameType, GetInfoMessage, GetServersMessage,
-    GetServersResponseMessage, HeartbeatMessage, Info, InfoKey, InfoResponseMessage, InfoValue,
-    ProtocolName,
+    Challenge, Command, FilterExtOptions, FilterOptions, GameName, GameType, GetInfoMessage,
+    GetServersExtMessage, GetServersMessage, GetServersResponseMessage, HeartbeatMessage, Info,
+    InfoKey, InfoResponseMessage, InfoValue, Message, Player, PrintMessage, ProtocolName,
+    ProtocolNumber, StatusResponseMessage, EOT_LEN, IPV4_ENTRY_LEN, MESSAGE_PREFIX_LEN,
 };
 use nom::branch::alt;
-use nom::bytes::complete::{tag, take_while, take_while1};
-use nom::character::{is_digit, is_newline};
-use nom::combinator::{opt, rest};
+use nom::bytes::complete::{tag, take_while, take_while1, take_while_m_n};
+use nom::character::is_digit;
+use nom::combinator::{eof, map, map_opt, map_res, not, opt, peek, recognize, rest, value};
 use nom::error::context;
-use nom::multi::{many1, many_till, separated_list0};
+use nom::multi::{fold_many1, many1, separated_list0};
 use nom::number::complete::{be_u16, be_u8};
-use nom::sequence::{preceded, tuple};
+use nom::sequence::{delimited, preceded, terminated, tuple};
 use nom::IResult;
 use std::net::{Ipv4Addr, SocketAddrV4};
+use std::ops::ControlFlow;
+use whitespace::{is_newline, is_space, newlines, space1, spaces, ws};
+
+/// Parsing mode
+///
+/// Parsers without a `mode` argument use [`Mode::Strict`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum Mode {
+    /// Only accept input as emitted by the reference implementation
+    #[default]
+    Strict,
+    /// Additionally accept nonstandard input as emitted by some other implementations
+    ///
+    /// * tabs as well as spaces between fields
+    /// * `\r\n` as well as `\n` line endings
+    /// * a leading `+` on the protocol number
+    /// * `\EOT` followed by less than three NUL bytes at the end of the message as end-of-transmission
+    /// * a `getserversResponse` message without the command token, i.e. the message prefix directly followed by the servers
+    /// * a `getserversResponse` message with neither servers nor EOT marker
+    /// * an `infoResponse` message without the `\n` between the command token and the info
+    Lenient,
+}
+
+/// Nonstandard input accepted by [`Mode::Lenient`]
+///
+/// Returned by [`parse_message_verbose`], e.g. for a master server to log which clients send nonstandard messages.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ParseWarning {
+    /// Tab instead of space between fields, at `offset` into the message
+    TabSeparator {
+        /// Offset of the tab into the message
+        offset: usize,
+    },
+    /// `\EOT` followed by less than three NUL bytes
+    ShortEotPadding,
+    /// `\r` of a `\r\n` line ending, at `offset` into the message
+    CarriageReturn {
+        /// Offset of the carriage return into the message
+        offset: usize,
+    },
+    /// `getserversResponse` message without the command token
+    MissingCommand,
+    /// `infoResponse` message without the `\n` between the command token and the info
+    MissingInfoSeparator,
+}
 
+/// dpmaster-specific error kinds
+///
+/// Analogous to [`nom::error::ErrorKind`], but for the parsers of this crate.\
+/// Unlike the former these do not change with the `nom` version, so they are a stable way to classify failures,
+/// e.g. via [`DeserializationError::Dpmaster`](crate::error::DeserializationError::Dpmaster).
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum ErrorKind {
+    /// Missing or invalid `\xFF\xFF\xFF\xFF` message prefix
     MessagePrefix,
+    /// More servers than the maximum in a `getserversResponse` message, see [`DEFAULT_MAX_SERVERS`]
+    TooManyServers,
+    /// Missing or unknown command token, see [`parse_command`]
+    Command,
+    /// Server socket address cut short in a `getserversResponse` message
+    TruncatedAddress,
+    /// Missing, zero or overflowing protocol number in a `getservers` or `getserversExt` message
+    ProtocolNumber,
+    /// `getserversResponse` message with neither servers nor EOT marker, which is likely a truncated packet
+    ///
+    /// Only in [`Mode::Strict`].
+    EmptyNonTerminalResponse,
+    /// Missing `\n` between the command token and the info in an `infoResponse` message
+    ///
+    /// Only in [`Mode::Strict`].
+    MissingInfoSeparator,
 }
 
+/// Error trait for the parsers of this crate
+///
+/// Extends [`nom::error::ParseError`] with dpmaster-specific [`ErrorKind`]s.\
+/// Implemented by [`DeserializationError`](crate::error::DeserializationError), [`VerboseError`] and `()`,
+/// but you can implement it for your own error type to collect errors as needed.
+///
+/// # Examples
+///
+/// ```rust
+/// use dpmaster_proto::deserializer::{message_prefix, ErrorKind, ParseError};
+///
+/// /// Remembers only the first dpmaster-specific error kind
+/// #[derive(Debug, PartialEq)]
+/// struct MyError(Option<ErrorKind>);
+///
+/// impl<I> nom::error::ParseError<I> for MyError {
+///     fn from_error_kind(_input: I, _kind: nom::error::ErrorKind) -> Self {
+///         Self(None)
+///     }
+///
+///     fn append(_input: I, _kind: nom::error::ErrorKind, other: Self) -> Self {
+///         other
+///     }
+/// }
+///
+/// impl<I> ParseError<I> for MyError {
+///     fn from_dpmaster_error_kind(_input: I, kind: ErrorKind) -> Self {
+///         Self(Some(kind))
+///     }
+///
+///     fn append_dpmaster(_input: I, kind: ErrorKind, other: Self) -> Self {
+///         Self(other.0.or(Some(kind)))
+///     }
+/// }
+///
+/// impl<I> nom::error::ContextError<I> for MyError {}
+///
+/// assert_eq!(
+///     message_prefix::<MyError>(b"hurz"),
+///     Err(nom::Err::Error(MyError(Some(ErrorKind::MessagePrefix))))
+/// );
+/// ```
 pub trait ParseError<I>: nom::error::ParseError<I> {
+    /// Creates an error from the input position and a dpmaster-specific [`ErrorKind`]
     fn from_dpmaster_error_kind(input: I, kind: ErrorKind) -> Self;
+    /// Combines an existing error with a new one created from the input position and a dpmaster-specific [`ErrorKind`]
+    ///
+    /// This is used by [`append`] when a parser fails.
     fn append_dpmaster(input: I, kind: ErrorKind, other: Self) -> Self;
 }
 
+/// Error kinds collected in a [`VerboseError`]
 #[derive(Clone, Debug, PartialEq)]
 pub enum VerboseErrorKind {
+    /// Static string added by [`nom::error::context`]
     Context(&'static str),
+    /// Indicates which character was expected
     Char(char),
+    /// Error kind given by various nom parsers
     Nom(nom::error::ErrorKind),
+    /// Error kind given by the parsers of this crate
     Dpmaster(ErrorKind),
 }
 
+/// Error type accumulating errors and their position in the input
+///
+/// Like [`nom::error::VerboseError`], but with support for the dpmaster-specific [`ErrorKind`].
 #[derive(Clone, Debug, PartialEq)]
 pub struct VerboseError<I> {
+    /// List of errors accumulated, with the input position they occurred at
     pub errors: Vec<(I, VerboseErrorKind)>,
 }
 
+impl<I> VerboseError<I> {
+    /// Returns whether the dpmaster-specific `kind` is one of the accumulated errors.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::deserializer::{message_prefix, ErrorKind, VerboseError};
+    /// let err = match message_prefix::<VerboseError<_>>(b"hurz") {
+    ///     Err(nom::Err::Error(err)) => err,
+    ///     _ => unreachable!(),
+    /// };
+    /// assert!(err.contains_kind(ErrorKind::MessagePrefix));
+    /// ```
+    pub fn contains_kind(&self, kind: ErrorKind) -> bool {
+        self.errors
+            .iter()
+            .any(|(_, k)| VerboseErrorKind::Dpmaster(kind) == *k)
+    }
+
+    /// Returns the innermost context added by [`nom::error::context`], if any.
+    pub fn first_context(&self) -> Option<&'static str> {
+        self.errors.iter().find_map(|(_, kind)| match kind {
+            VerboseErrorKind::Context(ctx) => Some(*ctx),
+            _ => None,
+        })
+    }
+}
+
 impl<I> nom::error::ParseError<I> for VerboseError<I> {
     fn from_error_kind(input: I, kind: nom::error::ErrorKind) -> Self {
         VerboseError {
@@ -85,6 +238,12 @@ impl<I> nom::error::ContextError<I> for VerboseError<I> {
     }
 }
 
+impl<I, E> nom::error::FromExternalError<I, E> for VerboseError<I> {
+    fn from_external_error(input: I, kind: nom::error::ErrorKind, _err: E) -> Self {
+        nom::error::ParseError::from_error_kind(input, kind)
+    }
+}
+
 impl<I: std::fmt::Display> std::fmt::Display for VerboseError<I> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Parse error:")?;
@@ -101,7 +260,10 @@ impl<I: std::fmt::Display> std::fmt::Display for VerboseError<I> {
     }
 }
 
-fn append<I: Clone, E: ParseError<I>, F, O>(
+/// Wraps the parser `f` to append the dpmaster-specific `kind` to its errors
+///
+/// Analogous to [`nom::error::context`], but using [`ParseError::append_dpmaster`].
+pub fn append<I: Clone, E: ParseError<I>, F, O>(
     kind: ErrorKind,
     mut f: F,
 ) -> impl FnMut(I) -> IResult<I, O, E>
@@ -127,44 +289,180 @@ where
     )(input)
 }
 
-fn protocol_name(input: &[u8]) -> IResult<&[u8], ProtocolName, DeserializationError<&[u8]>> {
-    let (input, protocol_name) = take_while1(|chr| !(is_newline(chr)))(input)?;
-    Ok((input, ProtocolName::new(protocol_name.to_vec()).unwrap())) // TODO
+/// Returns the [`Command`] of a whole message datagram, without parsing the message payload
+///
+/// This is a fast path e.g. to dispatch a datagram to the parser for its message type.\
+/// Returns `None` if the datagram is too short, lacks the message prefix or the command is unknown.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::{deserializer::message_command, messages::Command};
+/// assert_eq!(message_command(b"\xFF\xFF\xFF\xFFgetservers 68"), Some(Command::GetServers));
+/// assert_eq!(message_command(b"\xFF\xFF"), None);
+/// ```
+pub fn message_command(input: &[u8]) -> Option<Command> {
+    if input.len() < MESSAGE_PREFIX_LEN || input[..MESSAGE_PREFIX_LEN] != b"\xFF\xFF\xFF\xFF"[..] {
+        return None;
+    }
+
+    let payload = &input[MESSAGE_PREFIX_LEN..];
+    let len = payload
+        .iter()
+        .position(|chr| !chr.is_ascii_alphabetic())
+        .unwrap_or(payload.len());
+    Command::from_bytes(&payload[..len])
+}
+
+/// Parser for the message prefix and command token of a message
+///
+/// Returns the [`Command`] and the remaining payload, e.g. to dispatch to your own payload parsers.\
+/// Unlike [`message_command`] this composes with other parsers and reports why the input is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::{deserializer::parse_command, messages::Command};
+/// let result = parse_command::<()>(b"\xFF\xFF\xFF\xFFgetservers 68");
+/// assert_eq!(result, Ok((&b" 68"[..], Command::GetServers)));
+/// ```
+pub fn parse_command<'a, Error>(input: &'a [u8]) -> IResult<&'a [u8], Command, Error>
+where
+    Error: ParseError<&'a [u8]> + nom::error::ContextError<&'a [u8]>,
+{
+    preceded(
+        message_prefix,
+        context(
+            "command",
+            append(
+                ErrorKind::Command,
+                map_opt(
+                    take_while1(|chr: u8| chr.is_ascii_alphabetic()),
+                    Command::from_bytes,
+                ),
+            ),
+        ),
+    )(input)
+}
+
+/// Returns whether a whole `getserversResponse` or `getserversExtResponse` datagram ends with the EOT marker
+///
+/// This is a fast path e.g. to decide whether to wait for more responses without parsing the server addresses.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::deserializer::is_eot;
+/// assert!(is_eot(b"\xFF\xFF\xFF\xFFgetserversResponse\\EOT\0\0\0"));
+/// assert!(!is_eot(b"\xFF\xFF"));
+/// ```
+pub fn is_eot(input: &[u8]) -> bool {
+    if input.len() < MESSAGE_PREFIX_LEN + EOT_LEN
+        || input[..MESSAGE_PREFIX_LEN] != b"\xFF\xFF\xFF\xFF"[..]
+    {
+        return false;
+    }
+
+    input[input.len() - EOT_LEN..] == b"\\EOT\0\0\0"[..]
+}
+
+fn protocol_name_bytes(
+    mode: Mode,
+    input: &[u8],
+) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
+    let is_newline = is_newline(mode);
+    let (input, protocol_name) = take_while1(|chr| !is_newline(chr))(input)?;
+    Ok((input, protocol_name))
+}
+
+fn protocol_name(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], ProtocolName, DeserializationError<&[u8]>> {
+    move |input| {
+        let (input, protocol_name) = protocol_name_bytes(mode, input)?;
+        Ok((input, ProtocolName::new(protocol_name.to_vec()).unwrap())) // TODO
+    }
 }
 
 fn heartbeat_command(input: &[u8]) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
-    tag(b"heartbeat")(input)
+    tag(Command::Heartbeat.as_bytes())(input)
 }
 
 fn heartbeat_payload(
-    input: &[u8],
-) -> IResult<&[u8], HeartbeatMessage, DeserializationError<&[u8]>> {
-    let (input, (_, protocol_name, _)) =
-        tuple((take_while1(is_space), protocol_name, take_while(is_newline)))(input)?;
-    Ok((input, HeartbeatMessage::new(protocol_name)))
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], HeartbeatMessage, DeserializationError<&[u8]>> {
+    move |input| {
+        let (input, (_, protocol_name, _)) =
+            tuple((space1(mode), protocol_name(mode), newlines(mode)))(input)?;
+        Ok((input, HeartbeatMessage::new(protocol_name)))
+    }
 }
 
 pub fn heartbeat(input: &[u8]) -> IResult<&[u8], HeartbeatMessage, DeserializationError<&[u8]>> {
-    preceded(heartbeat_command, heartbeat_payload)(input)
+    heartbeat_with(Mode::Strict)(input)
+}
+
+pub fn heartbeat_with(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], HeartbeatMessage, DeserializationError<&[u8]>> {
+    move |input| preceded(heartbeat_command, heartbeat_payload(mode))(input)
 }
 
 pub fn heartbeat_message(
     input: &[u8],
 ) -> IResult<&[u8], HeartbeatMessage, DeserializationError<&[u8]>> {
-    preceded(message_prefix, heartbeat)(input)
+    heartbeat_message_with(Mode::Strict)(input)
+}
+
+pub fn heartbeat_message_with(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], HeartbeatMessage, DeserializationError<&[u8]>> {
+    move |input| preceded(message_prefix, heartbeat_with(mode))(input)
+}
+
+/// Like [`heartbeat_message_with`], but shares the allocation of repeated protocol names with the `interner`
+#[cfg(feature = "intern")]
+pub fn heartbeat_message_interned(
+    mode: Mode,
+    interner: &mut crate::intern::Interner,
+) -> impl FnMut(
+    &[u8],
+) -> IResult<&[u8], crate::intern::SharedProtocolName, DeserializationError<&[u8]>>
+       + '_ {
+    move |input| {
+        let (input, (_, _, _, protocol_name, _)) = tuple((
+            message_prefix,
+            heartbeat_command,
+            space1(mode),
+            |input| protocol_name_bytes(mode, input),
+            newlines(mode),
+        ))(input)?;
+        Ok((input, interner.protocol_name(protocol_name)))
+    }
 }
 
 fn getinfo_command(input: &[u8]) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
-    tag(b"getinfo")(input)
+    tag(Command::GetInfo.as_bytes())(input)
 }
 
 fn challenge(input: &[u8]) -> IResult<&[u8], Challenge, DeserializationError<&[u8]>> {
-    let (input, challenge) = rest(input)?;
-    Ok((input, Challenge::new(challenge.to_vec()).unwrap())) // TODO
+    let is_space = is_space(Mode::Strict);
+    let is_newline = is_newline(Mode::Strict);
+    let (input, challenge) = map_res(
+        take_while1(|chr| !(is_space(chr) || is_newline(chr))),
+        Challenge::new,
+    )(input)?;
+    Ok((input, challenge))
 }
 
 fn getinfo_payload(input: &[u8]) -> IResult<&[u8], GetInfoMessage, DeserializationError<&[u8]>> {
-    let (input, (_, challenge)) = tuple((take_while1(is_space), challenge))(input)?;
+    // some senders terminate the challenge with a space or newline
+    let (input, (_, challenge, _, _)) = tuple((
+        space1(Mode::Strict),
+        challenge,
+        spaces(Mode::Strict),
+        newlines(Mode::Strict),
+    ))(input)?;
     Ok((input, GetInfoMessage::new(challenge)))
 }
 
@@ -179,7 +477,7 @@ pub fn getinfo_message(
 }
 
 fn inforesponse_command(input: &[u8]) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
-    tag(b"infoResponse")(input)
+    tag(Command::InfoResponse.as_bytes())(input)
 }
 
 fn info_key(input: &[u8]) -> IResult<&[u8], InfoKey, DeserializationError<&[u8]>> {
@@ -188,7 +486,8 @@ fn info_key(input: &[u8]) -> IResult<&[u8], InfoKey, DeserializationError<&[u8]>
 }
 
 fn info_value(input: &[u8]) -> IResult<&[u8], InfoValue, DeserializationError<&[u8]>> {
-    let (input, v) = take_while1(|chr| b'\\' != chr)(input)?;
+    // values can be empty, e.g. `\pure\\g_needpass\0`
+    let (input, v) = take_while(|chr| b'\\' != chr)(input)?;
     Ok((input, InfoValue::new(v.to_vec()).unwrap())) // TODO
 }
 
@@ -198,54 +497,182 @@ fn info_kv(input: &[u8]) -> IResult<&[u8], (InfoKey, InfoValue), Deserialization
 }
 
 fn info(input: &[u8]) -> IResult<&[u8], Info, DeserializationError<&[u8]>> {
-    let (input, kv) = many1(info_kv)(input)?;
-    let mut info = Info::new();
-    for (key, value) in kv {
-        info.insert(key, value);
-    }
+    // each key-value pair has two `\` separators, so this is an upper bound for the number of pairs
+    let capacity = memchr::memchr_iter(b'\\', input).count() / 2;
+    info_into(Info::with_capacity(capacity), input)
+}
+
+fn info_into(mut info: Info, input: &[u8]) -> IResult<&[u8], Info, DeserializationError<&[u8]>> {
+    // insert directly instead of collecting the pairs into an intermediate `Vec`
+    let (input, ()) = fold_many1(info_kv, (), |(), (key, value)| info.insert(key, value))(input)?;
     Ok((input, info))
 }
 
-fn inforesponse_payload(
+fn info_separator(
+    mode: Mode,
     input: &[u8],
-) -> IResult<&[u8], InfoResponseMessage, DeserializationError<&[u8]>> {
-    let (input, (_, info)) = tuple((tag(b"\n"), info))(input)?;
-    Ok((input, InfoResponseMessage::new(info)))
+) -> IResult<&[u8], Option<&[u8]>, DeserializationError<&[u8]>> {
+    match mode {
+        Mode::Strict => map(append(ErrorKind::MissingInfoSeparator, tag(b"\n")), Some)(input),
+        Mode::Lenient => opt(tag(b"\n"))(input),
+    }
+}
+
+fn inforesponse_payload(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], InfoResponseMessage, DeserializationError<&[u8]>> {
+    move |input| {
+        let (input, (_, info)) = tuple((|input| info_separator(mode, input), info))(input)?;
+        Ok((input, InfoResponseMessage::new(info)))
+    }
 }
 
 pub fn inforesponse(
     input: &[u8],
 ) -> IResult<&[u8], InfoResponseMessage, DeserializationError<&[u8]>> {
-    preceded(inforesponse_command, inforesponse_payload)(input)
+    inforesponse_with(Mode::Strict)(input)
+}
+
+pub fn inforesponse_with(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], InfoResponseMessage, DeserializationError<&[u8]>> {
+    move |input| preceded(inforesponse_command, inforesponse_payload(mode))(input)
 }
 
 pub fn inforesponse_message(
     input: &[u8],
 ) -> IResult<&[u8], InfoResponseMessage, DeserializationError<&[u8]>> {
-    preceded(message_prefix, inforesponse)(input)
+    inforesponse_message_with(Mode::Strict)(input)
 }
 
-fn getservers_command(input: &[u8]) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
-    tag(b"getservers")(input)
+pub fn inforesponse_message_with(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], InfoResponseMessage, DeserializationError<&[u8]>> {
+    move |input| preceded(message_prefix, inforesponse_with(mode))(input)
+}
+
+/// Like [`inforesponse_message`], but grows the `Info` from empty instead of pre-sizing it
+///
+/// Only to compare against the capacity hint in `benches/info.rs`, not part of the stable API.
+#[doc(hidden)]
+pub fn inforesponse_message_unhinted(
+    input: &[u8],
+) -> IResult<&[u8], InfoResponseMessage, DeserializationError<&[u8]>> {
+    let (input, (_, _, _, info)) = tuple((
+        message_prefix,
+        inforesponse_command,
+        |input| info_separator(Mode::Strict, input),
+        |input| info_into(Info::new(), input),
+    ))(input)?;
+    Ok((input, InfoResponseMessage::new(info)))
+}
+
+fn statusresponse_command(input: &[u8]) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
+    tag(Command::StatusResponse.as_bytes())(input)
+}
+
+fn status_info_value(input: &[u8]) -> IResult<&[u8], InfoValue, DeserializationError<&[u8]>> {
+    // unlike in an `infoResponse`, the info is terminated by a newline
+    let (input, v) = take_while(|chr| b'\\' != chr && b'\n' != chr)(input)?;
+    Ok((input, InfoValue::new(v.to_vec()).unwrap())) // TODO
+}
+
+fn status_info(input: &[u8]) -> IResult<&[u8], Info, DeserializationError<&[u8]>> {
+    let (input, kv) = many1(tuple((
+        preceded(tag(b"\\"), info_key),
+        preceded(tag(b"\\"), status_info_value),
+    )))(input)?;
+    let mut info = Info::new();
+    for (key, value) in kv {
+        info.insert(key, value);
+    }
+    Ok((input, info))
+}
+
+fn player(input: &[u8]) -> IResult<&[u8], Player, DeserializationError<&[u8]>> {
+    let (input, (score, _, ping, _, name)) = tuple((
+        map_res(
+            map_res(
+                recognize(preceded(opt(tag(b"-")), take_while1(is_digit))),
+                std::str::from_utf8,
+            ),
+            str::parse,
+        ),
+        space1(Mode::Strict),
+        map_res(
+            map_res(take_while1(is_digit), std::str::from_utf8),
+            str::parse,
+        ),
+        space1(Mode::Strict),
+        delimited(tag(b"\""), take_while(|chr| b'"' != chr), tag(b"\"")),
+    ))(input)?;
+    Ok((input, Player::new(score, ping, name.to_vec())))
 }
 
-fn is_space(chr: u8) -> bool {
-    b' ' == chr
+fn statusresponse_payload(
+    input: &[u8],
+) -> IResult<&[u8], StatusResponseMessage, DeserializationError<&[u8]>> {
+    let (input, (_, info, _, players, _)) = tuple((
+        tag(b"\n"),
+        status_info,
+        newlines(Mode::Strict),
+        separated_list0(tag(b"\n"), player),
+        newlines(Mode::Strict),
+    ))(input)?;
+    Ok((input, StatusResponseMessage::new(info, players)))
+}
+
+pub fn statusresponse(
+    input: &[u8],
+) -> IResult<&[u8], StatusResponseMessage, DeserializationError<&[u8]>> {
+    preceded(statusresponse_command, statusresponse_payload)(input)
+}
+
+pub fn statusresponse_message(
+    input: &[u8],
+) -> IResult<&[u8], StatusResponseMessage, DeserializationError<&[u8]>> {
+    preceded(message_prefix, statusresponse)(input)
 }
 
-fn game_name(input: &[u8]) -> IResult<&[u8], Option<GameName>, DeserializationError<&[u8]>> {
-    let (input, game_name) = opt(take_while1(|chr| !(is_digit(chr) || is_space(chr))))(input)?;
-    Ok((
-        input,
-        game_name.map(|game_name| GameName::new(game_name.to_vec()).unwrap()),
-    )) // TODO
+fn getservers_command(input: &[u8]) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
+    tag(Command::GetServers.as_bytes())(input)
+}
+
+fn game_name(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], GameName, DeserializationError<&[u8]>> {
+    move |input| {
+        let is_space = is_space(mode);
+        let (input, game_name) = map_res(
+            take_while1(|chr| !(is_digit(chr) || is_space(chr))),
+            GameName::new_borrowed,
+        )(input)?;
+        Ok((input, game_name))
+    }
 }
 
-fn protocol_number(input: &[u8]) -> IResult<&[u8], u32, DeserializationError<&[u8]>> {
-    let (input, protocol_bytes) = take_while(is_digit)(input)?;
-    let protocol_str = std::str::from_utf8(protocol_bytes).unwrap(); // TODO
-    let protocol_number = u32::from_str_radix(protocol_str, 10).unwrap(); // TODO
-    Ok((input, protocol_number))
+fn protocol_number(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], ProtocolNumber, DeserializationError<&[u8]>> {
+    move |input| {
+        append(ErrorKind::ProtocolNumber, |input| match mode {
+            Mode::Strict => map_res(
+                map_res(take_while1(is_digit), std::str::from_utf8),
+                str::parse,
+            )(input),
+            Mode::Lenient => delimited(
+                spaces(mode),
+                map_res(
+                    map_res(
+                        recognize(preceded(opt(tag(b"+")), take_while1(is_digit))),
+                        std::str::from_utf8,
+                    ),
+                    str::parse,
+                ),
+                spaces(mode),
+            )(input),
+        })(input)
+    }
 }
 
 enum FilterOption {
@@ -255,13 +682,30 @@ enum FilterOption {
 }
 
 fn filteroption_gametype(
-    input: &[u8],
-) -> IResult<&[u8], FilterOption, DeserializationError<&[u8]>> {
-    let (input, gametype) = preceded(tag(b"gametype="), take_while1(|chr| chr != b' '))(input)?;
-    Ok((
-        input,
-        FilterOption::GameType(GameType::new(gametype.to_vec()).unwrap()),
-    ))
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], FilterOption, DeserializationError<&[u8]>> {
+    move |input| {
+        let is_space = is_space(mode);
+        let (input, gametype) =
+            preceded(tag(b"gametype="), take_while1(|chr| !is_space(chr)))(input)?;
+        Ok((
+            input,
+            FilterOption::GameType(GameType::new(gametype.to_vec()).unwrap()),
+        ))
+    }
+}
+
+fn filteroption_gametype_keyword<'g>(
+    mode: Mode,
+    game_name: Option<&'g GameName>,
+) -> impl Fn(&[u8]) -> IResult<&[u8], FilterOption, DeserializationError<&[u8]>> + 'g {
+    move |input| {
+        let is_space = is_space(mode);
+        let (input, gametype) = map_opt(take_while1(|chr| !is_space(chr)), |keyword| {
+            GameType::from_keyword(game_name, keyword)
+        })(input)?;
+        Ok((input, FilterOption::GameType(gametype)))
+    }
 }
 
 fn filteroption_empty(input: &[u8]) -> IResult<&[u8], FilterOption, DeserializationError<&[u8]>> {
@@ -274,212 +718,947 @@ fn filteroption_full(input: &[u8]) -> IResult<&[u8], FilterOption, Deserializati
     Ok((input, FilterOption::Full))
 }
 
-fn filteroption(input: &[u8]) -> IResult<&[u8], FilterOption, DeserializationError<&[u8]>> {
-    alt((filteroption_gametype, filteroption_empty, filteroption_full))(input)
+fn filteroption<'g>(
+    mode: Mode,
+    game_name: Option<&'g GameName>,
+) -> impl Fn(&[u8]) -> IResult<&[u8], FilterOption, DeserializationError<&[u8]>> + 'g {
+    move |input| {
+        alt((
+            filteroption_gametype(mode),
+            filteroption_empty,
+            filteroption_full,
+            filteroption_gametype_keyword(mode, game_name),
+        ))(input)
+    }
 }
 
-fn filteroptions(input: &[u8]) -> IResult<&[u8], FilterOptions, DeserializationError<&[u8]>> {
-    let mut gametype: Option<GameType> = None;
-    let mut empty: bool = false;
-    let mut full: bool = false;
-
-    let (input, filteroptions) = separated_list0(tag(b" "), filteroption)(input)?;
-    for filteroption in filteroptions {
-        match filteroption {
-            FilterOption::GameType(g) => {
-                gametype = Some(g);
-            }
-            FilterOption::Empty => {
-                empty = true;
-            }
-            FilterOption::Full => {
-                full = true;
+fn filteroptions<'g>(
+    mode: Mode,
+    game_name: Option<&'g GameName>,
+) -> impl Fn(&[u8]) -> IResult<&[u8], FilterOptions, DeserializationError<&[u8]>> + 'g {
+    move |input| {
+        let mut gametype: Option<GameType> = None;
+        let mut empty: bool = false;
+        let mut full: bool = false;
+
+        let (input, filteroptions) =
+            separated_list0(ws(mode), filteroption(mode, game_name))(input)?;
+        for filteroption in filteroptions {
+            match filteroption {
+                FilterOption::GameType(g) => {
+                    gametype = Some(g);
+                }
+                FilterOption::Empty => {
+                    empty = true;
+                }
+                FilterOption::Full => {
+                    full = true;
+                }
             }
         }
-    }
 
-    Ok((input, FilterOptions::new(gametype, empty, full)))
+        Ok((input, FilterOptions::new(gametype, empty, full)))
+    }
 }
 
 fn getservers_payload(
-    input: &[u8],
-) -> IResult<&[u8], GetServersMessage, DeserializationError<&[u8]>> {
-    let (input, (_, game_name, _, protocol_number, _, filteroptions)) = tuple((
-        take_while1(is_space),
-        game_name,
-        take_while(is_space),
-        protocol_number,
-        take_while(is_space),
-        filteroptions,
-    ))(input)?;
-    Ok((
-        input,
-        GetServersMessage::new(game_name, protocol_number, filteroptions),
-    ))
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], GetServersMessage, DeserializationError<&[u8]>> {
+    move |input| {
+        let (input, (_, game_name, _, protocol_number, _)) = tuple((
+            space1(mode),
+            opt(preceded(not(protocol_number(mode)), game_name(mode))),
+            spaces(mode),
+            protocol_number(mode),
+            spaces(mode),
+        ))(input)?;
+        let (input, (filteroptions, _)) =
+            tuple((filteroptions(mode, game_name.as_ref()), spaces(mode)))(input)?;
+        Ok((
+            input,
+            GetServersMessage::new(game_name, protocol_number, filteroptions),
+        ))
+    }
 }
 
 pub fn getservers(input: &[u8]) -> IResult<&[u8], GetServersMessage, DeserializationError<&[u8]>> {
-    preceded(getservers_command, getservers_payload)(input)
+    getservers_with(Mode::Strict)(input)
+}
+
+pub fn getservers_with(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], GetServersMessage, DeserializationError<&[u8]>> {
+    move |input| preceded(getservers_command, getservers_payload(mode))(input)
 }
 
 pub fn getservers_message(
     input: &[u8],
 ) -> IResult<&[u8], GetServersMessage, DeserializationError<&[u8]>> {
-    preceded(message_prefix, getservers)(input)
+    getservers_message_with(Mode::Strict)(input)
 }
 
-fn socketaddr4(input: &[u8]) -> IResult<&[u8], SocketAddrV4, DeserializationError<&[u8]>> {
-    let (input, (a, b, c, d, port)) = tuple((be_u8, be_u8, be_u8, be_u8, be_u16))(input)?;
-    let ipv4addr = Ipv4Addr::new(a, b, c, d);
-    let socketaddrv4 = SocketAddrV4::new(ipv4addr, port);
-    Ok((input, socketaddrv4))
+pub fn getservers_message_with(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], GetServersMessage, DeserializationError<&[u8]>> {
+    move |input| preceded(message_prefix, getservers_with(mode))(input)
 }
 
-fn socketaddr4_separator(input: &[u8]) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
-    tag(b"\\")(input)
+fn getserversext_command(input: &[u8]) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
+    tag(Command::GetServersExt.as_bytes())(input)
+}
+
+enum FilterExtOption {
+    Base(FilterOption),
+    Ipv4,
+    Ipv6,
+}
+
+fn filterextoption<'g>(
+    mode: Mode,
+    game_name: Option<&'g GameName>,
+) -> impl Fn(&[u8]) -> IResult<&[u8], FilterExtOption, DeserializationError<&[u8]>> + 'g {
+    move |input| {
+        alt((
+            map(tag(b"ipv4"), |_| FilterExtOption::Ipv4),
+            map(tag(b"ipv6"), |_| FilterExtOption::Ipv6),
+            map(filteroption(mode, game_name), FilterExtOption::Base),
+        ))(input)
+    }
+}
+
+fn filterextoptions<'g>(
+    mode: Mode,
+    game_name: Option<&'g GameName>,
+) -> impl Fn(&[u8]) -> IResult<&[u8], FilterExtOptions, DeserializationError<&[u8]>> + 'g {
+    move |input| {
+        let mut gametype: Option<GameType> = None;
+        let mut empty: bool = false;
+        let mut full: bool = false;
+        let mut ipv4: bool = false;
+        let mut ipv6: bool = false;
+
+        let (input, filteroptions) =
+            separated_list0(ws(mode), filterextoption(mode, game_name))(input)?;
+        for filteroption in filteroptions {
+            match filteroption {
+                FilterExtOption::Base(FilterOption::GameType(g)) => {
+                    gametype = Some(g);
+                }
+                FilterExtOption::Base(FilterOption::Empty) => {
+                    empty = true;
+                }
+                FilterExtOption::Base(FilterOption::Full) => {
+                    full = true;
+                }
+                FilterExtOption::Ipv4 => {
+                    ipv4 = true;
+                }
+                FilterExtOption::Ipv6 => {
+                    ipv6 = true;
+                }
+            }
+        }
+
+        Ok((
+            input,
+            FilterExtOptions::new(gametype, empty, full, ipv4, ipv6),
+        ))
+    }
 }
 
-fn eot(input: &[u8]) -> IResult<&[u8], bool, DeserializationError<&[u8]>> {
-    match input {
-        b"\\EOT\0\0\0" => Ok((&input[7..], true)),
-        b"" => Ok((input, false)),
-        _ => Err(nom::Err::Error(nom::error::make_error(
+fn getserversext_payload(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], GetServersExtMessage, DeserializationError<&[u8]>> {
+    move |input| {
+        // unlike `getservers`, the game name is mandatory
+        let (input, (_, game_name, _, protocol_number, _)) = tuple((
+            space1(mode),
+            game_name(mode),
+            space1(mode),
+            protocol_number(mode),
+            spaces(mode),
+        ))(input)?;
+        let (input, (filteroptions, _)) =
+            tuple((filterextoptions(mode, Some(&game_name)), spaces(mode)))(input)?;
+        Ok((
             input,
-            nom::error::ErrorKind::Tag,
-        ))),
+            GetServersExtMessage::new(game_name, protocol_number, filteroptions),
+        ))
     }
 }
 
-fn getserversresponse_payload(
+pub fn getserversext(
     input: &[u8],
-) -> IResult<&[u8], GetServersResponseMessage, DeserializationError<&[u8]>> {
-    let (input, (servers, eot)) =
-        many_till(preceded(socketaddr4_separator, socketaddr4), eot)(input)?;
-    let getserversresponse = GetServersResponseMessage::new(servers, eot);
-    Ok((input, getserversresponse))
+) -> IResult<&[u8], GetServersExtMessage, DeserializationError<&[u8]>> {
+    getserversext_with(Mode::Strict)(input)
 }
 
-fn getserversresponse_command(input: &[u8]) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
-    tag(b"getserversResponse")(input)
+pub fn getserversext_with(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], GetServersExtMessage, DeserializationError<&[u8]>> {
+    move |input| preceded(getserversext_command, getserversext_payload(mode))(input)
 }
 
-pub fn getserversresponse(
+pub fn getserversext_message(
     input: &[u8],
-) -> IResult<&[u8], GetServersResponseMessage, DeserializationError<&[u8]>> {
-    preceded(getserversresponse_command, getserversresponse_payload)(input)
+) -> IResult<&[u8], GetServersExtMessage, DeserializationError<&[u8]>> {
+    getserversext_message_with(Mode::Strict)(input)
 }
 
-pub fn getserversresponse_message(
-    input: &[u8],
-) -> IResult<&[u8], GetServersResponseMessage, DeserializationError<&[u8]>> {
-    preceded(message_prefix, getserversresponse)(input)
+pub fn getserversext_message_with(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], GetServersExtMessage, DeserializationError<&[u8]>> {
+    move |input| preceded(message_prefix, getserversext_with(mode))(input)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn socketaddr4<'a, Error>(input: &'a [u8]) -> IResult<&'a [u8], SocketAddrV4, Error>
+where
+    Error: nom::error::ParseError<&'a [u8]>,
+{
+    let (input, (a, b, c, d, port)) = tuple((be_u8, be_u8, be_u8, be_u8, be_u16))(input)?;
+    let ipv4addr = Ipv4Addr::new(a, b, c, d);
+    let socketaddrv4 = SocketAddrV4::new(ipv4addr, port);
+    Ok((input, socketaddrv4))
+}
 
-    #[test]
-    fn test_message_prefix_empty() {
-        let data = &b""[..];
-        let result = message_prefix::<VerboseError<_>>(data);
-        assert_eq!(
-            result,
-            Err(nom::Err::Error(VerboseError {
-                errors: vec![
-                    (&b""[..], VerboseErrorKind::Nom(nom::error::ErrorKind::Tag)),
-                    (
-                        &b""[..],
-                        VerboseErrorKind::Dpmaster(ErrorKind::MessagePrefix)
-                    ),
-                    (&b""[..], VerboseErrorKind::Context("message prefix")),
-                ]
-            }))
-        );
-    }
+fn socketaddr4_separator<'a, Error>(input: &'a [u8]) -> IResult<&'a [u8], &'a [u8], Error>
+where
+    Error: nom::error::ParseError<&'a [u8]>,
+{
+    tag(b"\\")(input)
+}
 
-    #[test]
-    fn test_message_prefix_invalid() {
-        let data = &b"hurz"[..];
-        let result = message_prefix::<VerboseError<_>>(data);
-        assert_eq!(
-            result,
-            Err(nom::Err::Error(VerboseError {
-                errors: vec![
-                    (
-                        &b"hurz"[..],
-                        VerboseErrorKind::Nom(nom::error::ErrorKind::Tag)
-                    ),
-                    (
-                        &b"hurz"[..],
-                        VerboseErrorKind::Dpmaster(ErrorKind::MessagePrefix)
-                    ),
-                    (&b"hurz"[..], VerboseErrorKind::Context("message prefix")),
-                ]
-            }))
-        );
-    }
+/// Parser for the `\EOT\0\0\0` end-of-transmission marker of a `getserversResponse` or `getserversExtResponse` message
+///
+/// Returns the remaining input after the marker, so it composes with parsers for trailing data.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::deserializer::eot;
+/// let result = eot::<()>(b"\\EOT\0\0\0trailer");
+/// assert_eq!(result, Ok((&b"trailer"[..], &b"\\EOT\0\0\0"[..])));
+/// ```
+pub fn eot<'a, Error>(input: &'a [u8]) -> IResult<&'a [u8], &'a [u8], Error>
+where
+    Error: nom::error::ParseError<&'a [u8]>,
+{
+    eot_with(Mode::Strict)(input)
+}
 
-    #[test]
-    fn test_message_prefix() {
-        let data = b"\xFF\xFF\xFF\xFF";
-        let result = message_prefix::<()>(data);
-        assert_eq!(result, Ok((&b""[..], &b"\xFF\xFF\xFF\xFF"[..])));
+pub fn eot_with<'a, Error>(mode: Mode) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], &'a [u8], Error>
+where
+    Error: nom::error::ParseError<&'a [u8]>,
+{
+    move |input: &'a [u8]| {
+        recognize(tuple((tag(b"\\EOT"), |input: &'a [u8]| match mode {
+            Mode::Strict => tag(b"\0\0\0")(input),
+            // a server in 69.79.84.0/24 starts with `\EOT` as well, so only accept short padding at the very end
+            Mode::Lenient => alt((
+                tag(b"\0\0\0"),
+                terminated(take_while_m_n(0, 2, |chr| chr == b'\0'), eof),
+            ))(input),
+        })))(input)
     }
+}
 
-    #[test]
-    fn test_heartbeat_message_dp() {
-        let data = &b"heartbeat DarkPlaces\x0A"[..];
-        let result = heartbeat(data);
-        assert_eq!(
-            result,
-            Ok((
-                &vec![][..],
-                HeartbeatMessage::new(ProtocolName::new(b"DarkPlaces".to_vec()).unwrap(),)
-            ))
-        );
-    }
+fn end_of_servers<'a, Error>(mode: Mode) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], bool, Error>
+where
+    Error: nom::error::ParseError<&'a [u8]>,
+{
+    move |input| alt((value(true, eot_with(mode)), value(false, eof)))(input)
+}
 
-    #[test]
-    fn test_heartbeat_message_q3a() {
-        let data = &b"heartbeat QuakeArena-1\x0A"[..];
-        let result = heartbeat(data);
-        assert_eq!(
-            result,
-            Ok((
-                &vec![][..],
-                HeartbeatMessage::new(ProtocolName::new(b"QuakeArena-1".to_vec()).unwrap(),)
-            ))
-        );
-    }
+/// Default maximum number of servers in a `getserversResponse` message
+///
+/// Bounds the memory used for parsing a malicious message, while being generous compared to the few hundred servers per message sent by dpmaster.
+pub const DEFAULT_MAX_SERVERS: usize = 4096;
 
-    #[test]
-    fn test_heartbeat_message_rtcw() {
-        let data = &b"heartbeat Wolfenstein-1\x0A"[..];
-        let result = heartbeat(data);
-        assert_eq!(
-            result,
-            Ok((
-                &vec![][..],
-                HeartbeatMessage::new(ProtocolName::new(b"Wolfenstein-1".to_vec()).unwrap(),)
-            ))
-        );
+type ServersTillEnd = (Vec<SocketAddrV4>, bool);
+
+fn servers_till_end<'a, Error>(
+    mode: Mode,
+    max_servers: usize,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], ServersTillEnd, Error>
+where
+    Error: ParseError<&'a [u8]>,
+{
+    // like `many_till`, but bails out instead of allocating for more than `max_servers`
+    move |mut input| {
+        let mut servers = Vec::new();
+        loop {
+            match end_of_servers(mode)(input) {
+                Ok((input, eot)) => return Ok((input, (servers, eot))),
+                Err(nom::Err::Error(_)) => {}
+                Err(e) => return Err(e),
+            }
+            if servers.len() >= max_servers {
+                return Err(nom::Err::Error(Error::from_dpmaster_error_kind(
+                    input,
+                    ErrorKind::TooManyServers,
+                )));
+            }
+            let (tail, server) = preceded(
+                socketaddr4_separator,
+                append(ErrorKind::TruncatedAddress, socketaddr4),
+            )(input)?;
+            servers.push(server);
+            input = tail;
+        }
     }
+}
 
-    #[test]
-    fn test_heartbeat_message_woet() {
-        let data = &b"heartbeat EnemyTerritory-1\x0A"[..];
-        let result = heartbeat(data);
-        assert_eq!(
-            result,
-            Ok((
-                &vec![][..],
-                HeartbeatMessage::new(ProtocolName::new(b"EnemyTerritory-1".to_vec()).unwrap(),)
-            ))
-        );
+fn getserversresponse_payload<'a, Error>(
+    mode: Mode,
+    max_servers: usize,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], GetServersResponseMessage, Error>
+where
+    Error: ParseError<&'a [u8]>,
+{
+    move |input| {
+        let (input, (servers, eot)) = servers_till_end(mode, max_servers)(input)?;
+        if mode == Mode::Strict && servers.is_empty() && !eot {
+            return Err(nom::Err::Error(Error::from_dpmaster_error_kind(
+                input,
+                ErrorKind::EmptyNonTerminalResponse,
+            )));
+        }
+        let (input, trailer) = rest(input)?;
+        let mut getserversresponse = GetServersResponseMessage::new(servers, eot);
+        getserversresponse.set_trailer(trailer.to_vec());
+        Ok((input, getserversresponse))
     }
+}
 
-    #[test]
+fn getserversresponse_command<'a, Error>(input: &'a [u8]) -> IResult<&'a [u8], &'a [u8], Error>
+where
+    Error: nom::error::ParseError<&'a [u8]>,
+{
+    tag(Command::GetServersResponse.as_bytes())(input)
+}
+
+pub fn getserversresponse<'a, Error>(
+    input: &'a [u8],
+) -> IResult<&'a [u8], GetServersResponseMessage, Error>
+where
+    Error: ParseError<&'a [u8]> + nom::error::ContextError<&'a [u8]>,
+{
+    getserversresponse_with(Mode::Strict)(input)
+}
+
+pub fn getserversresponse_with<'a, Error>(
+    mode: Mode,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], GetServersResponseMessage, Error>
+where
+    Error: ParseError<&'a [u8]> + nom::error::ContextError<&'a [u8]>,
+{
+    getserversresponse_with_max_servers(mode, DEFAULT_MAX_SERVERS)
+}
+
+/// Like [`getserversresponse_with`], but fails with [`ErrorKind::TooManyServers`] for more than `max_servers` servers
+pub fn getserversresponse_with_max_servers<'a, Error>(
+    mode: Mode,
+    max_servers: usize,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], GetServersResponseMessage, Error>
+where
+    Error: ParseError<&'a [u8]> + nom::error::ContextError<&'a [u8]>,
+{
+    move |input| {
+        preceded(
+            getserversresponse_command,
+            getserversresponse_payload(mode, max_servers),
+        )(input)
+    }
+}
+
+pub fn getserversresponse_message<'a, Error>(
+    input: &'a [u8],
+) -> IResult<&'a [u8], GetServersResponseMessage, Error>
+where
+    Error: ParseError<&'a [u8]> + nom::error::ContextError<&'a [u8]>,
+{
+    getserversresponse_message_with(Mode::Strict)(input)
+}
+
+pub fn getserversresponse_message_with<'a, Error>(
+    mode: Mode,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], GetServersResponseMessage, Error>
+where
+    Error: ParseError<&'a [u8]> + nom::error::ContextError<&'a [u8]>,
+{
+    getserversresponse_message_with_max_servers(mode, DEFAULT_MAX_SERVERS)
+}
+
+/// Like [`getserversresponse_message_with`], but fails with [`ErrorKind::TooManyServers`] for more than `max_servers` servers
+pub fn getserversresponse_message_with_max_servers<'a, Error>(
+    mode: Mode,
+    max_servers: usize,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], GetServersResponseMessage, Error>
+where
+    Error: ParseError<&'a [u8]> + nom::error::ContextError<&'a [u8]>,
+{
+    move |input| {
+        let (input, _) = message_prefix(input)?;
+        match mode {
+            Mode::Strict => getserversresponse_with_max_servers(mode, max_servers)(input),
+            Mode::Lenient => alt((
+                getserversresponse_with_max_servers(mode, max_servers),
+                getserversresponse_commandless(mode, max_servers),
+            ))(input),
+        }
+    }
+}
+
+// some buggy master servers omit the command token, so accept the payload if it starts with a server
+fn getserversresponse_commandless<'a, Error>(
+    mode: Mode,
+    max_servers: usize,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], GetServersResponseMessage, Error>
+where
+    Error: ParseError<&'a [u8]>,
+{
+    move |input| {
+        let (input, _) = peek(tuple((
+            not(eot_with(mode)),
+            socketaddr4_separator,
+            socketaddr4,
+        )))(input)?;
+        getserversresponse_payload(mode, max_servers)(input)
+    }
+}
+
+/// Parses a whole `getserversResponse` message, calling `f` for each server instead of collecting them
+///
+/// Allocation-free counterpart to [`getserversresponse_message`] for constrained environments.
+/// Parsing stops early once `f` returns [`ControlFlow::Break`](std::ops::ControlFlow::Break).
+///
+/// Returns whether the message ended with an EOT marker, or `false` if `f` stopped early.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::deserializer::parse_getserversresponse_each;
+/// # use std::ops::ControlFlow;
+/// let mut servers = Vec::new();
+/// let eot = parse_getserversresponse_each::<()>(
+///     b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0",
+///     |server| {
+///         servers.push(server);
+///         ControlFlow::Continue(())
+///     },
+/// );
+/// assert_eq!(eot, Ok(true));
+/// assert_eq!(servers, vec!["1.2.3.4:2048".parse().unwrap()]);
+/// ```
+///
+/// # Errors
+///
+/// Will return an error if `input` is not a valid `getserversResponse` message.
+/// Servers before the invalid part have been passed to `f` already.
+pub fn parse_getserversresponse_each<'a, Error>(
+    input: &'a [u8],
+    mut f: impl FnMut(SocketAddrV4) -> ControlFlow<()>,
+) -> Result<bool, nom::Err<Error>>
+where
+    Error: ParseError<&'a [u8]> + nom::error::ContextError<&'a [u8]>,
+{
+    let (mut input, _) = preceded(message_prefix, getserversresponse_command)(input)?;
+    loop {
+        match end_of_servers(Mode::Strict)(input) {
+            Ok((_, eot)) => return Ok(eot),
+            Err(nom::Err::Error(_)) => {}
+            Err(e) => return Err(e),
+        }
+        let (tail, server) = preceded(
+            socketaddr4_separator,
+            append(ErrorKind::TruncatedAddress, socketaddr4),
+        )(input)?;
+        if let ControlFlow::Break(()) = f(server) {
+            return Ok(false);
+        }
+        input = tail;
+    }
+}
+
+/// Reassembles the servers of a `getserversResponse` message split into arbitrary chunks
+///
+/// A master server never splits a server address across datagrams, but a misframed or coalesced stream might.
+/// Unlike [`getserversresponse_message`], which needs the complete message, this buffers a trailing partial
+/// server address (or message prefix and command token, or EOT marker) until the next chunk completes it.
+///
+/// Only accepts the [`Mode::Strict`] format, and ignores anything after the EOT marker.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::deserializer::GetServersResponseReassembler;
+/// let mut reassembler = GetServersResponseReassembler::new();
+/// assert_eq!(reassembler.feed(b"\xFF\xFF\xFF\xFFgetserversResponse\\\xC0\x00")?, vec![]);
+/// assert!(reassembler.is_partial());
+/// assert_eq!(
+///     reassembler.feed(b"\x02\x01\x6D\x38\\EOT\0\0\0")?,
+///     vec!["192.0.2.1:27960".parse()?]
+/// );
+/// assert!(reassembler.eot());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct GetServersResponseReassembler {
+    buf: Vec<u8>,
+    header: bool,
+    eot: bool,
+}
+
+impl GetServersResponseReassembler {
+    /// Creates a new `GetServersResponseReassembler` expecting the start of a message.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the next `chunk` of the message, and returns the servers completed by it.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`ProtocolError::InvalidMessage`](crate::ProtocolError::InvalidMessage) if the bytes so far
+    /// can not be the start of a `getserversResponse` message. The reassembler should not be fed any more then.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<SocketAddrV4>, crate::ProtocolError> {
+        if self.eot {
+            return Ok(Vec::new());
+        }
+        self.buf.extend_from_slice(chunk);
+
+        if !self.header {
+            let header = [
+                &b"\xFF\xFF\xFF\xFF"[..],
+                Command::GetServersResponse.as_bytes(),
+            ]
+            .concat();
+            let len = self.buf.len().min(header.len());
+            if self.buf[..len] != header[..len] {
+                return Err(crate::ProtocolError::InvalidMessage);
+            }
+            if len < header.len() {
+                return Ok(Vec::new());
+            }
+            self.buf.drain(..header.len());
+            self.header = true;
+        }
+
+        let mut servers = Vec::new();
+        let mut consumed = 0;
+        while let Some(entry) = self.buf.get(consumed..consumed + IPV4_ENTRY_LEN) {
+            if eot::<()>(entry).is_ok() {
+                self.eot = true;
+                self.buf.clear();
+                return Ok(servers);
+            }
+            let (_, server) = preceded(socketaddr4_separator, socketaddr4::<()>)(entry)
+                .map_err(|_| crate::ProtocolError::InvalidMessage)?;
+            servers.push(server);
+            consumed += IPV4_ENTRY_LEN;
+        }
+        self.buf.drain(..consumed);
+        if matches!(self.buf.first(), Some(&byte) if byte != b'\\') {
+            return Err(crate::ProtocolError::InvalidMessage);
+        }
+        Ok(servers)
+    }
+
+    /// Returns whether the EOT marker has been fed.
+    pub fn eot(&self) -> bool {
+        self.eot
+    }
+
+    /// Returns whether bytes of an incomplete server address, message header or EOT marker are buffered.
+    pub fn is_partial(&self) -> bool {
+        !self.buf.is_empty()
+    }
+}
+
+fn print_command(input: &[u8]) -> IResult<&[u8], &[u8], DeserializationError<&[u8]>> {
+    tag(Command::Print.as_bytes())(input)
+}
+
+fn print_payload(input: &[u8]) -> IResult<&[u8], PrintMessage, DeserializationError<&[u8]>> {
+    let (input, text) = preceded(tag(b"\n"), rest)(input)?;
+    Ok((input, PrintMessage::new(text.to_vec())))
+}
+
+pub fn print(input: &[u8]) -> IResult<&[u8], PrintMessage, DeserializationError<&[u8]>> {
+    preceded(print_command, print_payload)(input)
+}
+
+pub fn print_message(input: &[u8]) -> IResult<&[u8], PrintMessage, DeserializationError<&[u8]>> {
+    preceded(message_prefix, print)(input)
+}
+
+/// Parser for any message
+pub fn parse_message(input: &[u8]) -> IResult<&[u8], Message, DeserializationError<&[u8]>> {
+    parse_message_with(Mode::Strict)(input)
+}
+
+pub fn parse_message_with(
+    mode: Mode,
+) -> impl Fn(&[u8]) -> IResult<&[u8], Message, DeserializationError<&[u8]>> {
+    move |input| {
+        alt((
+            map(heartbeat_message_with(mode), Message::Heartbeat),
+            map(getinfo_message, Message::GetInfo),
+            map(inforesponse_message_with(mode), Message::InfoResponse),
+            map(getservers_message_with(mode), Message::GetServers),
+            map(
+                getserversresponse_message_with(mode),
+                Message::GetServersResponse,
+            ),
+            map(getserversext_message_with(mode), Message::GetServersExt),
+            map(print_message, Message::Print),
+        ))(input)
+    }
+}
+
+/// Splits a buffer of concatenated messages into the messages and the number of bytes each consumed
+///
+/// This is e.g. for manual framing of a buffer filled from a raw socket. Each message is parsed with [`parse_message`],
+/// so a message which consumes the rest of its input, like a `getserversResponse` with a trailer, ends the buffer.
+///
+/// The iterator ends after the first error.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::deserializer::split_messages;
+/// let buf = b"\xFF\xFF\xFF\xFFheartbeat DarkPlaces\n\xFF\xFF\xFF\xFFgetservers 68";
+/// let consumed: Vec<usize> = split_messages(buf).map(|r| r.unwrap().1).collect();
+/// assert_eq!(consumed, vec![25, 17]);
+/// ```
+///
+/// # Errors
+///
+/// Yields [`ProtocolError::InvalidMessage`](crate::ProtocolError::InvalidMessage) for the remaining buffer if it does not start with a valid message.
+pub fn split_messages(
+    buf: &[u8],
+) -> impl Iterator<Item = Result<(Message, usize), crate::ProtocolError>> + '_ {
+    let mut remaining = Some(buf);
+    std::iter::from_fn(move || {
+        let input = remaining.filter(|input| !input.is_empty())?;
+        match parse_message(input) {
+            Ok((rest, message)) if rest.len() < input.len() => {
+                remaining = Some(rest);
+                Some(Ok((message, input.len() - rest.len())))
+            }
+            _ => {
+                remaining = None;
+                Some(Err(crate::ProtocolError::InvalidMessage))
+            }
+        }
+    })
+}
+
+// Tabs and carriage returns which the lenient whitespace parsers accepted in place of a space or line ending.
+// Unlike such a byte within a field, e.g. the protocol name, replacing it with its standard counterpart
+// does not change the parsed message.
+fn lenient_whitespace(parsed: &[u8], message: &Message) -> Vec<ParseWarning> {
+    let parses_to_message = |standard: &[u8]| match parse_message_with(Mode::Lenient)(standard) {
+        Ok((rest, reparsed)) => rest.is_empty() && reparsed == *message,
+        Err(_) => false,
+    };
+    parsed
+        .iter()
+        .enumerate()
+        .filter_map(|(offset, chr)| {
+            let mut standard = parsed.to_vec();
+            match chr {
+                b'\t' => {
+                    standard[offset] = b' ';
+                    parses_to_message(&standard).then_some(ParseWarning::TabSeparator { offset })
+                }
+                b'\r' => {
+                    standard.remove(offset);
+                    parses_to_message(&standard).then_some(ParseWarning::CarriageReturn { offset })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+type WithWarnings<T> = (T, Vec<ParseWarning>);
+
+/// Parser for any message in [`Mode::Lenient`] which also returns the nonstandard input it accepted
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::deserializer::{parse_message_verbose, ParseWarning};
+/// let (_, (message, warnings)) = parse_message_verbose(b"\xFF\xFF\xFF\xFFgetservers\t68")?;
+/// assert_eq!(warnings, vec![ParseWarning::TabSeparator { offset: 14 }]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn parse_message_verbose(
+    input: &[u8],
+) -> IResult<&[u8], WithWarnings<Message>, DeserializationError<&[u8]>> {
+    let (rest, message) = parse_message_with(Mode::Lenient)(input)?;
+    let parsed = &input[..input.len() - rest.len()];
+
+    let mut warnings = vec![];
+    match &message {
+        Message::Heartbeat(_) | Message::GetServers(_) | Message::GetServersExt(_) => {
+            // a standard message parses the same in strict mode, which is the common case
+            let standard = match parse_message_with(Mode::Strict)(input) {
+                Ok((strict_rest, strict)) => strict_rest == rest && strict == message,
+                Err(_) => false,
+            };
+            if !standard {
+                warnings.extend(lenient_whitespace(parsed, &message));
+            }
+        }
+        Message::GetServersResponse(getserversresponse) => {
+            if !parsed[MESSAGE_PREFIX_LEN..].starts_with(Command::GetServersResponse.as_bytes()) {
+                warnings.push(ParseWarning::MissingCommand);
+            }
+            if getserversresponse.eot()
+                && !parsed[..parsed.len() - getserversresponse.trailer().len()]
+                    .ends_with(b"\\EOT\0\0\0")
+            {
+                warnings.push(ParseWarning::ShortEotPadding);
+            }
+        }
+        Message::InfoResponse(_)
+            if !parsed[MESSAGE_PREFIX_LEN..].starts_with(b"infoResponse\n") =>
+        {
+            warnings.push(ParseWarning::MissingInfoSeparator);
+        }
+        _ => {}
+    }
+
+    Ok((rest, (message, warnings)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_prefix_empty() {
+        let data = &b""[..];
+        let result = message_prefix::<VerboseError<_>>(data);
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(VerboseError {
+                errors: vec![
+                    (&b""[..], VerboseErrorKind::Nom(nom::error::ErrorKind::Tag)),
+                    (
+                        &b""[..],
+                        VerboseErrorKind::Dpmaster(ErrorKind::MessagePrefix)
+                    ),
+                    (&b""[..], VerboseErrorKind::Context("message prefix")),
+                ]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_verbose_error_inspection() {
+        let err = VerboseError {
+            errors: vec![
+                (
+                    &b"hurz"[..],
+                    VerboseErrorKind::Nom(nom::error::ErrorKind::Tag),
+                ),
+                (
+                    &b"hurz"[..],
+                    VerboseErrorKind::Dpmaster(ErrorKind::MessagePrefix),
+                ),
+                (&b"hurz"[..], VerboseErrorKind::Context("message prefix")),
+                (&b"hurz"[..], VerboseErrorKind::Context("message")),
+            ],
+        };
+        assert!(err.contains_kind(ErrorKind::MessagePrefix));
+        assert_eq!(err.first_context(), Some("message prefix"));
+
+        let err: VerboseError<_> =
+            nom::error::ParseError::from_error_kind(&b"hurz"[..], nom::error::ErrorKind::Tag);
+        assert!(!err.contains_kind(ErrorKind::MessagePrefix));
+        assert_eq!(err.first_context(), None);
+    }
+
+    #[test]
+    fn test_message_prefix_invalid() {
+        let data = &b"hurz"[..];
+        let result = message_prefix::<VerboseError<_>>(data);
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(VerboseError {
+                errors: vec![
+                    (
+                        &b"hurz"[..],
+                        VerboseErrorKind::Nom(nom::error::ErrorKind::Tag)
+                    ),
+                    (
+                        &b"hurz"[..],
+                        VerboseErrorKind::Dpmaster(ErrorKind::MessagePrefix)
+                    ),
+                    (&b"hurz"[..], VerboseErrorKind::Context("message prefix")),
+                ]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_message_prefix() {
+        let data = b"\xFF\xFF\xFF\xFF";
+        let result = message_prefix::<()>(data);
+        assert_eq!(result, Ok((&b""[..], &b"\xFF\xFF\xFF\xFF"[..])));
+    }
+
+    #[test]
+    fn test_message_command_short() {
+        for data in [&b""[..], b"\xFF", b"\xFF\xFF\xFF", b"\xFF\xFF\xFF\xFF"] {
+            assert_eq!(message_command(data), None);
+        }
+    }
+
+    #[test]
+    fn test_message_command() {
+        assert_eq!(
+            message_command(b"\xFF\xFF\xFF\xFFheartbeat DarkPlaces\x0A"),
+            Some(Command::Heartbeat)
+        );
+        assert_eq!(
+            message_command(b"\xFF\xFF\xFF\xFFinfoResponse\x0A\\clients\\0"),
+            Some(Command::InfoResponse)
+        );
+        assert_eq!(
+            message_command(b"\xFF\xFF\xFF\xFFgetserversResponse\\EOT\0\0\0"),
+            Some(Command::GetServersResponse)
+        );
+        assert_eq!(message_command(b"\xFF\xFF\xFF\xFFhurz"), None);
+        assert_eq!(message_command(b"hurzgetservers 68"), None);
+    }
+
+    #[test]
+    fn test_parse_command() {
+        for command in [
+            Command::Heartbeat,
+            Command::GetInfo,
+            Command::InfoResponse,
+            Command::GetServers,
+            Command::GetServersResponse,
+            Command::GetServersExt,
+            Command::GetServersExtResponse,
+            Command::GetStatus,
+            Command::StatusResponse,
+            Command::Print,
+        ] {
+            let mut data = b"\xFF\xFF\xFF\xFF".to_vec();
+            data.extend_from_slice(command.as_bytes());
+            data.extend_from_slice(b"\npayload");
+            assert_eq!(parse_command::<()>(&data), Ok((&b"\npayload"[..], command)));
+        }
+    }
+
+    #[test]
+    fn test_parse_command_unknown() {
+        let err = parse_command::<VerboseError<_>>(b"\xFF\xFF\xFF\xFFhurz 68").unwrap_err();
+        match err {
+            nom::Err::Error(err) => assert!(err.contains_kind(ErrorKind::Command)),
+            _ => panic!("unexpected error {:?}", err),
+        }
+
+        let err = parse_command::<VerboseError<_>>(b"\xFF\xFF\xFF\xFF 68").unwrap_err();
+        match err {
+            nom::Err::Error(err) => assert!(err.contains_kind(ErrorKind::Command)),
+            _ => panic!("unexpected error {:?}", err),
+        }
+
+        assert!(parse_command::<()>(b"getservers 68").is_err());
+    }
+
+    #[test]
+    fn test_is_eot_short() {
+        for data in [&b""[..], b"\xFF", b"\xFF\xFF\xFF", b"\xFF\xFF\xFF\xFF"] {
+            assert!(!is_eot(data));
+        }
+    }
+
+    #[test]
+    fn test_is_eot() {
+        assert!(is_eot(b"\xFF\xFF\xFF\xFF\\EOT\0\0\0"));
+        assert!(is_eot(
+            b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0"
+        ));
+        assert!(!is_eot(
+            b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00"
+        ));
+        assert!(!is_eot(b"\\EOT\0\0\0"));
+    }
+
+    #[test]
+    fn test_heartbeat_message_dp() {
+        let data = &b"heartbeat DarkPlaces\x0A"[..];
+        let result = heartbeat(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                HeartbeatMessage::new(ProtocolName::new(b"DarkPlaces".to_vec()).unwrap(),)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_message_spaces() {
+        let data = &b"heartbeat My Game\x0A"[..];
+        let result = heartbeat(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                HeartbeatMessage::new(ProtocolName::new(b"My Game".to_vec()).unwrap(),)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_message_q3a() {
+        let data = &b"heartbeat QuakeArena-1\x0A"[..];
+        let result = heartbeat(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                HeartbeatMessage::new(ProtocolName::new(b"QuakeArena-1".to_vec()).unwrap(),)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_message_rtcw() {
+        let data = &b"heartbeat Wolfenstein-1\x0A"[..];
+        let result = heartbeat(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                HeartbeatMessage::new(ProtocolName::new(b"Wolfenstein-1".to_vec()).unwrap(),)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_message_woet() {
+        let data = &b"heartbeat EnemyTerritory-1\x0A"[..];
+        let result = heartbeat(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                HeartbeatMessage::new(ProtocolName::new(b"EnemyTerritory-1".to_vec()).unwrap(),)
+            ))
+        );
+    }
+
+    #[test]
     fn test_getinfo_message() {
         let data = &b"getinfo A_ch4Lleng3"[..];
         let result = getinfo(data);
@@ -487,95 +1666,540 @@ mod tests {
             result,
             Ok((
                 &vec![][..],
-                GetInfoMessage::new(Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap(),)
+                GetInfoMessage::new(Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap(),)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_inforesponse_message() {
+        let data = &b"infoResponse\x0A\\sv_maxclients\\8\\clients\\0"[..];
+        let result = inforesponse(data);
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
+            InfoValue::new(b"8".to_vec()).unwrap(),
+        );
+        info.insert(
+            InfoKey::new(b"clients".to_vec()).unwrap(),
+            InfoValue::new(b"0".to_vec()).unwrap(),
+        );
+        assert_eq!(result, Ok((&vec![][..], InfoResponseMessage::new(info),)));
+    }
+
+    #[test]
+    fn test_inforesponse_message_empty_value() {
+        let data = &b"infoResponse\x0A\\pure\\\\g_needpass\\0"[..];
+        let result = inforesponse(data);
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"pure".to_vec()).unwrap(),
+            InfoValue::new(b"".to_vec()).unwrap(),
+        );
+        info.insert(
+            InfoKey::new(b"g_needpass".to_vec()).unwrap(),
+            InfoValue::new(b"0".to_vec()).unwrap(),
+        );
+        assert_eq!(result, Ok((&vec![][..], InfoResponseMessage::new(info),)));
+    }
+
+    #[test]
+    fn test_inforesponse_message_empty_key() {
+        let data = &b"infoResponse\x0A\\\\0"[..];
+        let result = inforesponse(data);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_statusresponse_message() {
+        let data = &b"\xFF\xFF\xFF\xFFstatusResponse\n\\sv_hostname\\noname\\sv_maxclients\\8\n12 48 \"player\"\n3 0 \"Sarge\"\n-1 0 \"Grunt\"\n"[..];
+        let (rest, message) = statusresponse_message(data).unwrap();
+        assert_eq!(rest, b"");
+
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"sv_hostname".to_vec()).unwrap(),
+            InfoValue::new(b"noname".to_vec()).unwrap(),
+        );
+        info.insert(
+            InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
+            InfoValue::new(b"8".to_vec()).unwrap(),
+        );
+        assert_eq!(
+            message,
+            StatusResponseMessage::new(
+                info,
+                vec![
+                    Player::new(12, 48, b"player".to_vec()),
+                    Player::new(3, 0, b"Sarge".to_vec()),
+                    Player::new(-1, 0, b"Grunt".to_vec()),
+                ]
+            )
+        );
+        assert_eq!(message.player_count(), 3);
+        assert_eq!(message.bot_count(), 2);
+    }
+
+    #[test]
+    fn test_statusresponse_message_no_players() {
+        let data = &b"\xFF\xFF\xFF\xFFstatusResponse\n\\sv_maxclients\\8\n"[..];
+        let (rest, message) = statusresponse_message(data).unwrap();
+        assert_eq!(rest, b"");
+        assert_eq!(message.players(), &[]);
+    }
+
+    #[test]
+    #[cfg(feature = "intern")]
+    fn test_heartbeat_message_interned() {
+        let mut interner = crate::intern::Interner::new();
+        let mut parser = heartbeat_message_interned(Mode::Strict, &mut interner);
+        let data = &b"\xFF\xFF\xFF\xFFheartbeat DarkPlaces\x0A"[..];
+
+        let (rest, first) = parser(data).unwrap();
+        assert_eq!(rest, b"");
+        assert_eq!(&first[..], b"DarkPlaces");
+        let names: Vec<_> = (0..1000).map(|_| parser(data).unwrap().1).collect();
+        assert!(names.iter().all(|name| *name == first));
+
+        let (_, other) = parser(&b"\xFF\xFF\xFF\xFFheartbeat QuakeArena-1\x0A"[..]).unwrap();
+        assert_eq!(&other[..], b"QuakeArena-1");
+        drop(parser);
+        // a single allocation per distinct name, regardless of the number of parses
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn test_getserversext_message_ipv4_ipv6() {
+        let data = &b"\xFF\xFF\xFF\xFFgetserversExt Xonotic 3 empty full ipv4 ipv6"[..];
+        let (rest, message) = getserversext_message(data).unwrap();
+        assert_eq!(rest, b"");
+        assert_eq!(&message.game_name()[..], b"Xonotic");
+        assert_eq!(message.protocol_number(), ProtocolNumber::new(3).unwrap());
+        let filter_options = message.filter_options();
+        assert_eq!(filter_options.gametype(), None);
+        assert!(filter_options.empty());
+        assert!(filter_options.full());
+        assert!(filter_options.ipv4());
+        assert!(filter_options.ipv6());
+    }
+
+    #[test]
+    fn test_getserversext_message_ipv6_only() {
+        let data = &b"\xFF\xFF\xFF\xFFgetserversExt Xonotic 3 ipv6 gametype=ctf"[..];
+        let (rest, message) = getserversext_message(data).unwrap();
+        assert_eq!(rest, b"");
+        let filter_options = message.filter_options();
+        assert_eq!(
+            filter_options.gametype(),
+            Some(&GameType::new(b"ctf".to_vec()).unwrap())
+        );
+        assert!(!filter_options.empty());
+        assert!(!filter_options.full());
+        assert!(!filter_options.ipv4());
+        assert!(filter_options.ipv6());
+    }
+
+    #[test]
+    fn test_getserversext_message_no_options() {
+        let data = &b"\xFF\xFF\xFF\xFFgetserversExt Xonotic 3"[..];
+        let (_, message) = getserversext_message(data).unwrap();
+        assert_eq!(
+            message.filter_options(),
+            &FilterExtOptions::new(None, false, false, false, false)
+        );
+    }
+
+    #[test]
+    fn test_getserversext_message_missing_game_name() {
+        let data = &b"\xFF\xFF\xFF\xFFgetserversExt 3 ipv4"[..];
+        assert!(getserversext_message(data).is_err());
+    }
+
+    #[test]
+    fn test_parse_message_getserversext() {
+        let data = &b"\xFF\xFF\xFF\xFFgetserversExt Xonotic 3 ipv4"[..];
+        let (_, message) = parse_message(data).unwrap();
+        assert_eq!(message.command(), Command::GetServersExt);
+    }
+
+    #[test]
+    fn test_inforesponse_message_many_tiny_pairs() {
+        let mut data = b"\xFF\xFF\xFF\xFFinfoResponse\n".to_vec();
+        for i in 0..1000 {
+            data.extend_from_slice(format!("\\{}\\b", i).as_bytes());
+        }
+        let (rest, message) = inforesponse_message(&data).unwrap();
+        assert_eq!(rest, b"");
+
+        let info = message.info();
+        assert_eq!(info.iter().count(), 1000);
+        assert_eq!(info.capacity(), Info::with_capacity(1000).capacity());
+        let (key, value) = info.iter().last().unwrap();
+        assert_eq!((&key[..], &value[..]), (&b"999"[..], &b"b"[..]));
+    }
+
+    #[test]
+    fn test_inforesponse_message_capacity() {
+        let data = &b"\xFF\xFF\xFF\xFFinfoResponse\x0A\\sv_maxclients\\8\\clients\\0\\pure\\\\g_needpass\\0"[..];
+        let (rest, message) = inforesponse_message(data).unwrap();
+        assert_eq!(rest, b"");
+
+        let info = message.info();
+        assert_eq!(info.iter().count(), 4);
+        let mut expected = Info::new();
+        for (key, value) in [
+            (&b"sv_maxclients"[..], &b"8"[..]),
+            (b"clients", b"0"),
+            (b"pure", b""),
+            (b"g_needpass", b"0"),
+        ]
+        .iter()
+        {
+            expected.insert(InfoKey::new(*key).unwrap(), InfoValue::new(*value).unwrap());
+        }
+        assert_eq!(info, &expected);
+
+        // pre-sized for exactly the 4 pairs, i.e. it never grew while inserting
+        assert_eq!(info.capacity(), Info::with_capacity(4).capacity());
+        // unlike growing from empty
+        assert_ne!(expected.capacity(), info.capacity());
+    }
+
+    #[test]
+    fn test_print_message() {
+        let data = &b"\xFF\xFF\xFF\xFFprint\nYou are banned from this master server\n"[..];
+        let result = print_message(data);
+        assert_eq!(
+            result,
+            Ok((
+                &b""[..],
+                PrintMessage::new(b"You are banned from this master server\n".to_vec())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_message_print() {
+        let data = &b"\xFF\xFF\xFF\xFFprint\nInvalid request\n"[..];
+        let (rest, message) = parse_message(data).unwrap();
+        assert_eq!(rest, b"");
+        assert_eq!(
+            message,
+            Message::Print(PrintMessage::new(b"Invalid request\n".to_vec()))
+        );
+        assert_eq!(message_command(data), Some(Command::Print));
+    }
+
+    #[test]
+    fn test_getservers_message_q3a() {
+        let data = &b"getservers 67 gametype=0 empty full"[..];
+        let result = getservers(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                GetServersMessage::new(
+                    None,
+                    ProtocolNumber::new(67).unwrap(),
+                    FilterOptions::new(Some(GameType::new(b"0".to_vec()).unwrap()), true, true)
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn test_getservers_message_gametype_keyword() {
+        let data = &b"getservers 68 empty ffa"[..];
+        let result = getservers(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                GetServersMessage::new(
+                    None,
+                    ProtocolNumber::new(68).unwrap(),
+                    FilterOptions::new(Some(GameType::new(b"0".to_vec()).unwrap()), true, false)
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn test_getservers_message_gametype_keyword_unknown() {
+        let data = &b"getservers Nexuiz 3 ffa"[..];
+        let (rest, getservers) = getservers(data).unwrap();
+        assert_eq!(rest, b"ffa");
+        assert_eq!(getservers.filter_options().gametype(), None);
+    }
+
+    #[test]
+    fn test_getservers_message_woet() {
+        let data = &b"getservers 84"[..];
+        let result = getservers(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                GetServersMessage::new(
+                    None,
+                    ProtocolNumber::new(84).unwrap(),
+                    FilterOptions::new(None, false, false)
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn test_getservers_message_nexuiz() {
+        let data = &b"getservers Nexuiz 3"[..];
+        let result = getservers(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                GetServersMessage::new(
+                    Some(GameName::new(b"Nexuiz".to_vec()).unwrap()),
+                    ProtocolNumber::new(3).unwrap(),
+                    FilterOptions::new(None, false, false)
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn test_getservers_message_qfusion() {
+        let data = &b"getservers qfusion 39 full"[..];
+        let result = getservers(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                GetServersMessage::new(
+                    Some(GameName::new(b"qfusion".to_vec()).unwrap()),
+                    ProtocolNumber::new(39).unwrap(),
+                    FilterOptions::new(None, false, true)
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn test_getservers_message_without_protocol_number() {
+        let data = &b"getservers   "[..];
+        let result = getservers(data);
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(DeserializationError::Dpmaster(
+                &b""[..],
+                ErrorKind::ProtocolNumber
+            )))
+        );
+    }
+
+    #[test]
+    fn test_getservers_message_zero_protocol_number() {
+        let data = &b"getservers 0"[..];
+        let result = getservers(data);
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(DeserializationError::Dpmaster(
+                &b"0"[..],
+                ErrorKind::ProtocolNumber
+            )))
+        );
+    }
+
+    #[test]
+    fn test_getservers_message_overflowing_protocol_number() {
+        let data = &b"getservers Nexuiz 4294967296"[..];
+        let result = getservers(data);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_getservers_message_plus_protocol_number() {
+        let data = &b"getservers +67"[..];
+        let (_, message) = getservers_with(Mode::Lenient)(data).unwrap();
+        assert_eq!(message.game_name(), None);
+        assert_eq!(message.protocol_number(), ProtocolNumber::new(67).unwrap());
+
+        let data = &b"getservers Nexuiz +3 empty"[..];
+        let (_, message) = getservers_with(Mode::Lenient)(data).unwrap();
+        assert_eq!(
+            message.game_name(),
+            Some(&GameName::new(b"Nexuiz".to_vec()).unwrap())
+        );
+        assert_eq!(message.protocol_number(), ProtocolNumber::new(3).unwrap());
+        assert!(message.filter_options().empty());
+
+        let data = &b"getservers Nexuiz +3"[..];
+        assert!(getservers(data).is_err());
+    }
+
+    #[test]
+    fn test_protocol_number_lenient() {
+        let result = protocol_number(Mode::Lenient)(b"+67");
+        assert_eq!(result, Ok((&b""[..], ProtocolNumber::new(67).unwrap())));
+
+        let result = protocol_number(Mode::Lenient)(b"  67  ");
+        assert_eq!(result, Ok((&b""[..], ProtocolNumber::new(67).unwrap())));
+
+        assert!(protocol_number(Mode::Lenient)(b"-67").is_err());
+        assert!(protocol_number(Mode::Lenient)(b"+-67").is_err());
+        assert!(protocol_number(Mode::Strict)(b"+67").is_err());
+    }
+
+    #[test]
+    fn test_getservers_message_spaces_around_protocol_number() {
+        let data = &b"getservers  67  "[..];
+        for mode in [Mode::Strict, Mode::Lenient] {
+            let (rest, message) = getservers_with(mode)(data).unwrap();
+            assert_eq!(rest, b"");
+            assert_eq!(message.protocol_number(), ProtocolNumber::new(67).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_getservers_message_trailing_space() {
+        let data = &b"getservers 84 "[..];
+        let result = getservers(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                GetServersMessage::new(
+                    None,
+                    ProtocolNumber::new(84).unwrap(),
+                    FilterOptions::new(None, false, false)
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn test_getservers_message_filters_trailing_space() {
+        let data = &b"getservers 84 empty full "[..];
+        let result = getservers(data);
+        assert_eq!(
+            result,
+            Ok((
+                &vec![][..],
+                GetServersMessage::new(
+                    None,
+                    ProtocolNumber::new(84).unwrap(),
+                    FilterOptions::new(None, true, true)
+                )
             ))
         );
     }
 
     #[test]
-    fn test_inforesponse_message() {
-        let data = &b"infoResponse\x0A\\sv_maxclients\\8\\clients\\0"[..];
-        let result = inforesponse(data);
-        let mut info = Info::new();
-        info.insert(
-            InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
-            InfoValue::new(b"8".to_vec()).unwrap(),
-        );
-        info.insert(
-            InfoKey::new(b"clients".to_vec()).unwrap(),
-            InfoValue::new(b"0".to_vec()).unwrap(),
-        );
-        assert_eq!(result, Ok((&vec![][..], InfoResponseMessage::new(info),)));
+    fn test_getservers_message_tabs_strict() {
+        let data = &b"getservers\t84"[..];
+        let result = getservers(data);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_getservers_message_q3a() {
-        let data = &b"getservers 67 gametype=0 empty full"[..];
-        let result = getservers(data);
+    fn test_getservers_message_tabs_lenient() {
+        let data = &b"getservers\tqfusion\t39\tgametype=ctf\tfull"[..];
+        let result = getservers_with(Mode::Lenient)(data);
         assert_eq!(
             result,
             Ok((
                 &vec![][..],
                 GetServersMessage::new(
-                    None,
-                    67,
-                    FilterOptions::new(Some(GameType::new(b"0".to_vec()).unwrap()), true, true)
+                    Some(GameName::new(b"qfusion".to_vec()).unwrap()),
+                    ProtocolNumber::new(39).unwrap(),
+                    FilterOptions::new(Some(GameType::new(b"ctf".to_vec()).unwrap()), false, true)
                 )
             ))
         );
     }
 
     #[test]
-    fn test_getservers_message_woet() {
-        let data = &b"getservers 84"[..];
-        let result = getservers(data);
+    fn test_getservers_message_tab_lenient() {
+        let data = &b"getservers\t84"[..];
+        let result = getservers_with(Mode::Lenient)(data);
         assert_eq!(
             result,
             Ok((
                 &vec![][..],
-                GetServersMessage::new(None, 84, FilterOptions::new(None, false, false))
+                GetServersMessage::new(
+                    None,
+                    ProtocolNumber::new(84).unwrap(),
+                    FilterOptions::new(None, false, false)
+                )
             ))
         );
     }
 
     #[test]
-    fn test_getservers_message_nexuiz() {
-        let data = &b"getservers Nexuiz 3"[..];
-        let result = getservers(data);
+    fn test_heartbeat_message_tab_lenient() {
+        let data = &b"heartbeat\tDarkPlaces\x0A"[..];
+        assert!(heartbeat(data).is_err());
+        let result = heartbeat_with(Mode::Lenient)(data);
         assert_eq!(
             result,
             Ok((
                 &vec![][..],
-                GetServersMessage::new(
-                    Some(GameName::new(b"Nexuiz".to_vec()).unwrap()),
-                    3,
-                    FilterOptions::new(None, false, false)
-                )
+                HeartbeatMessage::new(ProtocolName::new(b"DarkPlaces".to_vec()).unwrap(),)
             ))
         );
     }
 
     #[test]
-    fn test_getservers_message_qfusion() {
-        let data = &b"getservers qfusion 39 full"[..];
-        let result = getservers(data);
+    fn test_heartbeat_message_crlf() {
+        let data = &b"heartbeat DarkPlaces\x0D\x0A"[..];
+        let (rest, heartbeat_message) = heartbeat(data).unwrap();
+        assert_eq!(rest, b"");
+        assert_eq!(
+            heartbeat_message.protocol_name(),
+            &ProtocolName::new(b"DarkPlaces\x0D".to_vec()).unwrap()
+        );
+
+        let result = heartbeat_with(Mode::Lenient)(data);
         assert_eq!(
             result,
             Ok((
                 &vec![][..],
-                GetServersMessage::new(
-                    Some(GameName::new(b"qfusion".to_vec()).unwrap()),
-                    39,
-                    FilterOptions::new(None, false, true)
-                )
+                HeartbeatMessage::new(ProtocolName::new(b"DarkPlaces".to_vec()).unwrap(),)
             ))
         );
     }
 
+    #[test]
+    fn test_eot() {
+        let result = eot::<()>(b"\\EOT\0\0\0");
+        assert_eq!(result, Ok((&b""[..], &b"\\EOT\0\0\0"[..])));
+
+        let result = eot::<()>(b"\\EOT\0\0\0d0pk");
+        assert_eq!(result, Ok((&b"d0pk"[..], &b"\\EOT\0\0\0"[..])));
+
+        assert!(eot::<()>(b"\\EOT\0").is_err());
+        assert!(eot::<()>(b"").is_err());
+    }
+
+    #[test]
+    fn test_eot_lenient() {
+        let result = eot_with::<()>(Mode::Lenient)(b"\\EOT");
+        assert_eq!(result, Ok((&b""[..], &b"\\EOT"[..])));
+
+        let result = eot_with::<()>(Mode::Lenient)(b"\\EOT\0");
+        assert_eq!(result, Ok((&b""[..], &b"\\EOT\0"[..])));
+
+        let result = eot_with::<()>(Mode::Lenient)(b"\\EOT\0\0\0d0pk");
+        assert_eq!(result, Ok((&b"d0pk"[..], &b"\\EOT\0\0\0"[..])));
+
+        // short padding followed by anything else is a server in 69.79.84.0/24
+        assert!(eot_with::<()>(Mode::Lenient)(b"\\EOT\0d0pk").is_err());
+        assert!(eot_with::<()>(Mode::Lenient)(b"\\EOT\x01\x6D\x38").is_err());
+    }
+
     #[test]
     fn test_getserversresponse_multiple() {
         let data = &b"getserversResponse\\\xC0\x00\x02\x01\x6D\x38\\\xC6\x33\x64\x02\x6D\x39\\\xCB\x00\x71\x03\x6D\x3A"[..];
-        let result = getserversresponse(data);
+        let result = getserversresponse::<DeserializationError<_>>(data);
         assert_eq!(
             result,
             Ok((
@@ -595,7 +2219,7 @@ mod tests {
     #[test]
     fn test_getserversresponse_eot() {
         let data = &b"getserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0"[..];
-        let result = getserversresponse(data);
+        let result = getserversresponse::<DeserializationError<_>>(data);
         assert_eq!(
             result,
             Ok((
@@ -607,4 +2231,539 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_getserversresponse_message_empty_eot() {
+        let data = &b"\xFF\xFF\xFF\xFFgetserversResponse\\EOT\0\0\0"[..];
+        let result = getserversresponse_message::<DeserializationError<_>>(data);
+        assert_eq!(
+            result,
+            Ok((&vec![][..], GetServersResponseMessage::new(vec![], true)))
+        );
+    }
+
+    #[test]
+    fn test_getserversresponse_trailer() {
+        let data = &b"getserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0d0pk\x01\x02"[..];
+        let (rest, getserversresponse) =
+            getserversresponse::<DeserializationError<_>>(data).unwrap();
+        assert_eq!(rest, b"");
+        assert!(getserversresponse.eot());
+        assert_eq!(
+            getserversresponse,
+            [SocketAddrV4::new(Ipv4Addr::new(1, 2, 3, 4), 2048)][..]
+        );
+        assert_eq!(getserversresponse.trailer(), b"d0pk\x01\x02");
+    }
+
+    #[test]
+    fn test_getserversresponse_max_servers() {
+        let data =
+            &b"getserversResponse\\\x01\x02\x03\x04\x08\x00\\\x05\x06\x07\x08\x08\x00\\EOT\0\0\0"[..];
+
+        let (rest, message) =
+            getserversresponse_with_max_servers::<DeserializationError<_>>(Mode::Strict, 2)(data)
+                .unwrap();
+        assert_eq!(rest, b"");
+        assert_eq!(message.servers().len(), 2);
+
+        let result =
+            getserversresponse_with_max_servers::<DeserializationError<_>>(Mode::Strict, 1)(data);
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(DeserializationError::Dpmaster(
+                &b"\\\x05\x06\x07\x08\x08\x00\\EOT\0\0\0"[..],
+                ErrorKind::TooManyServers
+            )))
+        );
+    }
+
+    #[test]
+    fn test_getserversresponse_default_max_servers() {
+        let mut data = b"getserversResponse".to_vec();
+        for _ in 0..DEFAULT_MAX_SERVERS {
+            data.extend_from_slice(b"\\\x01\x02\x03\x04\x08\x00");
+        }
+        data.extend_from_slice(b"\\EOT\0\0\0");
+        let (_, message) = getserversresponse::<DeserializationError<_>>(&data).unwrap();
+        assert_eq!(message.servers().len(), DEFAULT_MAX_SERVERS);
+
+        data.splice(18..18, b"\\\x01\x02\x03\x04\x08\x00".iter().copied());
+        let err = getserversresponse::<VerboseError<_>>(&data).unwrap_err();
+        match err {
+            nom::Err::Error(err) => assert!(err.contains_kind(ErrorKind::TooManyServers)),
+            _ => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_getserversresponse_message_commandless() {
+        let data = &b"\xFF\xFF\xFF\xFF\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0"[..];
+
+        let result = getserversresponse_message::<DeserializationError<_>>(data);
+        assert!(result.is_err());
+
+        let result =
+            getserversresponse_message_with::<DeserializationError<_>>(Mode::Lenient)(data);
+        assert_eq!(
+            result,
+            Ok((
+                &b""[..],
+                GetServersResponseMessage::new(vec!["1.2.3.4:2048".parse().unwrap()], true)
+            ))
+        );
+
+        let (_, (message, warnings)) = parse_message_verbose(data).unwrap();
+        assert_eq!(message.command(), Command::GetServersResponse);
+        assert_eq!(warnings, vec![ParseWarning::MissingCommand]);
+    }
+
+    #[test]
+    fn test_getserversresponse_message_commandless_not_servers() {
+        for data in [
+            &b"\xFF\xFF\xFF\xFF"[..],
+            &b"\xFF\xFF\xFF\xFF\\EOT\0\0\0"[..],
+            &b"\xFF\xFF\xFF\xFF\\\x01\x02"[..],
+            &b"\xFF\xFF\xFF\xFFhurz"[..],
+        ]
+        .iter()
+        {
+            let result =
+                getserversresponse_message_with::<DeserializationError<_>>(Mode::Lenient)(data);
+            assert!(result.is_err(), "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn test_parse_getserversresponse_each() {
+        let data =
+            b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00\\\x05\x06\x07\x08\x10\x00\\EOT\0\0\0";
+        let mut servers = Vec::new();
+        let result = parse_getserversresponse_each::<()>(data, |server| {
+            servers.push(server);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(result, Ok(true));
+        assert_eq!(
+            servers,
+            vec![
+                "1.2.3.4:2048".parse::<SocketAddrV4>().unwrap(),
+                "5.6.7.8:4096".parse().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_getserversresponse_each_no_eot() {
+        let data = b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00";
+        let mut count = 0;
+        let result = parse_getserversresponse_each::<()>(data, |_| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+        assert_eq!(result, Ok(false));
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_parse_getserversresponse_each_break() {
+        let data =
+            b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00\\\x05\x06\x07\x08\x10\x00\\EOT\0\0\0";
+        let mut servers = Vec::new();
+        let result = parse_getserversresponse_each::<()>(data, |server| {
+            servers.push(server);
+            ControlFlow::Break(())
+        });
+        assert_eq!(result, Ok(false));
+        assert_eq!(
+            servers,
+            vec!["1.2.3.4:2048".parse::<SocketAddrV4>().unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_parse_getserversresponse_each_invalid() {
+        assert!(
+            parse_getserversresponse_each::<()>(b"hurz", |_| ControlFlow::Continue(())).is_err()
+        );
+        let data = b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02";
+        assert!(parse_getserversresponse_each::<()>(data, |_| ControlFlow::Continue(())).is_err());
+    }
+
+    #[test]
+    fn test_getservers_invalid_game_name() {
+        for data in [
+            &b"getservers Nex\nuiz 3"[..],
+            b"getservers Nex\ruiz 3",
+            b"getservers Nex\\uiz 3",
+            b"getservers Nex\0uiz 3",
+        ] {
+            assert!(getservers(data).is_err());
+        }
+    }
+
+    #[test]
+    fn test_error_kind_message_prefix() {
+        let result = getservers_message(b"\xFF\xFFgetservers 68");
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(DeserializationError::Dpmaster(
+                &b"\xFF\xFFgetservers 68"[..],
+                ErrorKind::MessagePrefix
+            )))
+        );
+    }
+
+    #[test]
+    fn test_error_kind_command() {
+        let result = parse_command::<DeserializationError<_>>(b"\xFF\xFF\xFF\xFFhurz 68");
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(DeserializationError::Dpmaster(
+                &b"hurz 68"[..],
+                ErrorKind::Command
+            )))
+        );
+    }
+
+    #[test]
+    fn test_error_kind_truncated_address() {
+        let result = getserversresponse::<DeserializationError<_>>(
+            b"getserversResponse\\\x01\x02\x03\x04\x08\x00\\\x05\x06",
+        );
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(DeserializationError::Dpmaster(
+                &b"\x05\x06"[..],
+                ErrorKind::TruncatedAddress
+            )))
+        );
+    }
+
+    #[test]
+    fn test_error_kind_protocol_number() {
+        let result = getservers(b"getservers Nexuiz 4294967296");
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(DeserializationError::Dpmaster(
+                &b"4294967296"[..],
+                ErrorKind::ProtocolNumber
+            )))
+        );
+    }
+
+    #[test]
+    fn test_split_messages() {
+        let buf = b"\xFF\xFF\xFF\xFFheartbeat DarkPlaces\n\xFF\xFF\xFF\xFFgetservers 68 empty";
+        let messages: Vec<_> = split_messages(buf).collect();
+        assert_eq!(
+            messages,
+            vec![
+                Ok((
+                    Message::Heartbeat(HeartbeatMessage::new(ProtocolName::default())),
+                    25
+                )),
+                Ok((
+                    Message::GetServers(GetServersMessage::new(
+                        None,
+                        ProtocolNumber::new(68).unwrap(),
+                        FilterOptions::new(None, true, false),
+                    )),
+                    23
+                )),
+            ]
+        );
+        assert_eq!(split_messages(b"").count(), 0);
+    }
+
+    #[test]
+    fn test_split_messages_invalid() {
+        let buf = b"\xFF\xFF\xFF\xFFheartbeat DarkPlaces\nhurz";
+        let messages: Vec<_> = split_messages(buf).collect();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].as_ref().map(|(_, len)| *len), Ok(25));
+        assert_eq!(messages[1], Err(crate::ProtocolError::InvalidMessage));
+    }
+
+    #[test]
+    fn test_getserversresponse_empty_non_terminal_strict() {
+        let result = getserversresponse::<DeserializationError<_>>(b"getserversResponse");
+        assert_eq!(
+            result,
+            Err(nom::Err::Error(DeserializationError::Dpmaster(
+                &b""[..],
+                ErrorKind::EmptyNonTerminalResponse
+            )))
+        );
+
+        // either servers or the EOT marker make it valid
+        assert!(getserversresponse::<()>(b"getserversResponse\\EOT\0\0\0").is_ok());
+        assert!(getserversresponse::<()>(b"getserversResponse\\\x01\x02\x03\x04\x08\x00").is_ok());
+    }
+
+    #[test]
+    fn test_getserversresponse_empty_non_terminal_lenient() {
+        let (_, message) =
+            getserversresponse_with::<()>(Mode::Lenient)(b"getserversResponse").unwrap();
+        assert_eq!(message, GetServersResponseMessage::new(vec![], false));
+    }
+
+    #[test]
+    fn test_inforesponse_missing_separator_strict() {
+        let data = &b"infoResponse\\sv_maxclients\\8"[..];
+        let result = inforesponse(data);
+        assert!(matches!(
+            result,
+            Err(nom::Err::Error(DeserializationError::Dpmaster(
+                _,
+                ErrorKind::MissingInfoSeparator
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_inforesponse_missing_separator_lenient() {
+        let data = &b"infoResponse\\sv_maxclients\\8"[..];
+        let (rest, message) = inforesponse_with(Mode::Lenient)(data).unwrap();
+        assert_eq!(rest, &b""[..]);
+        let (key, value) = message.info().iter().next().unwrap();
+        assert_eq!((&key[..], &value[..]), (&b"sv_maxclients"[..], &b"8"[..]));
+
+        // the separator is still accepted
+        let data = &b"infoResponse\n\\sv_maxclients\\8"[..];
+        assert_eq!(inforesponse_with(Mode::Lenient)(data), inforesponse(data));
+    }
+
+    #[test]
+    fn test_parse_message_verbose_missing_info_separator() {
+        let data = &b"\xFF\xFF\xFF\xFFinfoResponse\\sv_maxclients\\8"[..];
+        let (_, (message, warnings)) = parse_message_verbose(data).unwrap();
+        assert!(matches!(message, Message::InfoResponse(_)));
+        assert_eq!(warnings, vec![ParseWarning::MissingInfoSeparator]);
+
+        let data = &b"\xFF\xFF\xFF\xFFinfoResponse\n\\sv_maxclients\\8"[..];
+        let (_, (_, warnings)) = parse_message_verbose(data).unwrap();
+        assert_eq!(warnings, vec![]);
+    }
+
+    #[test]
+    fn test_getserversresponse_reassembler_split_address() {
+        let mut reassembler = GetServersResponseReassembler::new();
+        assert_eq!(
+            reassembler
+                .feed(b"\xFF\xFF\xFF\xFFgetserversResponse\\\xC0\x00\x02\x01\x6D\x38\\\xC6\x33"),
+            Ok(vec![SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960)])
+        );
+        assert!(reassembler.is_partial());
+        assert!(!reassembler.eot());
+
+        assert_eq!(
+            reassembler.feed(b"\x64\x02\x6D\x39\\EO"),
+            Ok(vec![SocketAddrV4::new(
+                Ipv4Addr::new(198, 51, 100, 2),
+                27961
+            )])
+        );
+        assert!(!reassembler.eot());
+
+        assert_eq!(reassembler.feed(b"T\0\0\0trailer"), Ok(vec![]));
+        assert!(reassembler.eot());
+        assert!(!reassembler.is_partial());
+        assert_eq!(reassembler.feed(b"\\\xC0\x00\x02\x01\x6D\x38"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_getserversresponse_reassembler_split_header() {
+        let data = &b"\xFF\xFF\xFF\xFFgetserversResponse\\\xC0\x00\x02\x01\x6D\x38\\EOT\0\0\0"[..];
+        let mut reassembler = GetServersResponseReassembler::new();
+        let servers: Vec<_> = data
+            .chunks(1)
+            .flat_map(|chunk| reassembler.feed(chunk).unwrap())
+            .collect();
+        assert_eq!(
+            servers,
+            vec![SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960)]
+        );
+        assert!(reassembler.eot());
+    }
+
+    #[test]
+    fn test_getserversresponse_reassembler_invalid() {
+        let mut reassembler = GetServersResponseReassembler::new();
+        assert_eq!(
+            reassembler.feed(b"\xFF\xFF\xFF\xFFgetinfo"),
+            Err(crate::ProtocolError::InvalidMessage)
+        );
+
+        let mut reassembler = GetServersResponseReassembler::new();
+        assert_eq!(
+            reassembler.feed(b"\xFF\xFF\xFF\xFFgetserversResponse/\xC0"),
+            Err(crate::ProtocolError::InvalidMessage)
+        );
+    }
+
+    #[test]
+    fn test_getserversresponse_eot_short_strict() {
+        for data in [
+            &b"getserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT"[..],
+            &b"getserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0"[..],
+        ] {
+            let result = getserversresponse::<DeserializationError<_>>(data);
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn test_getserversresponse_eot_short_lenient() {
+        for data in [
+            &b"getserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT"[..],
+            &b"getserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0"[..],
+            &b"getserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0"[..],
+        ] {
+            let result = getserversresponse_with::<DeserializationError<_>>(Mode::Lenient)(data);
+            assert_eq!(
+                result,
+                Ok((
+                    &vec![][..],
+                    GetServersResponseMessage::new(
+                        vec![SocketAddrV4::new(Ipv4Addr::new(1, 2, 3, 4), 2048),],
+                        true
+                    )
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn test_getserversresponse_eot_like_server_lenient() {
+        let data =
+            &b"getserversResponse\\\x45\x4F\x54\x01\x6D\x38\\\xC0\x00\x02\x01\x6D\x38\\EOT\0\0\0"[..];
+        for mode in [Mode::Strict, Mode::Lenient] {
+            let result = getserversresponse_with::<DeserializationError<_>>(mode)(data);
+            assert_eq!(
+                result,
+                Ok((
+                    &vec![][..],
+                    GetServersResponseMessage::new(
+                        vec![
+                            SocketAddrV4::new(Ipv4Addr::new(69, 79, 84, 1), 27960),
+                            SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960),
+                        ],
+                        true
+                    )
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_message() {
+        let data = &b"\xFF\xFF\xFF\xFFgetservers 68 empty"[..];
+        let (_, message) = parse_message(data).unwrap();
+        assert_eq!(message.command(), Command::GetServers);
+
+        let data = &b"\xFF\xFF\xFF\xFFgetserversResponse\\EOT\0\0\0"[..];
+        let (_, message) = parse_message(data).unwrap();
+        assert_eq!(
+            message,
+            Message::GetServersResponse(GetServersResponseMessage::new(vec![], true))
+        );
+
+        assert!(parse_message(b"\xFF\xFF\xFF\xFFhurz").is_err());
+    }
+
+    #[test]
+    fn test_parse_message_verbose_standard() {
+        let data = &b"\xFF\xFF\xFF\xFFheartbeat DarkPlaces\x0A"[..];
+        let (_, (_, warnings)) = parse_message_verbose(data).unwrap();
+        assert_eq!(warnings, vec![]);
+    }
+
+    #[test]
+    fn test_parse_message_verbose_tabs() {
+        let data = &b"\xFF\xFF\xFF\xFFgetservers\tNexuiz 3\tempty"[..];
+        let (_, (message, warnings)) = parse_message_verbose(data).unwrap();
+        assert_eq!(message.command(), Command::GetServers);
+        assert_eq!(
+            warnings,
+            vec![
+                ParseWarning::TabSeparator { offset: 14 },
+                ParseWarning::TabSeparator { offset: 23 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_message_verbose_crlf() {
+        let data = &b"\xFF\xFF\xFF\xFFheartbeat DarkPlaces\x0D\x0A"[..];
+        let (rest, (message, warnings)) = parse_message_verbose(data).unwrap();
+        assert_eq!(rest, b"");
+        assert_eq!(
+            message,
+            Message::Heartbeat(HeartbeatMessage::new(ProtocolName::default()))
+        );
+        assert_eq!(warnings, vec![ParseWarning::CarriageReturn { offset: 24 }]);
+    }
+
+    #[test]
+    fn test_parse_message_verbose_short_eot() {
+        let data = &b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0"[..];
+        let (_, (message, warnings)) = parse_message_verbose(data).unwrap();
+        assert_eq!(message.command(), Command::GetServersResponse);
+        assert_eq!(warnings, vec![ParseWarning::ShortEotPadding]);
+    }
+
+    #[test]
+    fn test_parse_message_verbose_eot_like_server() {
+        let data = &b"\xFF\xFF\xFF\xFFgetserversResponse\\\x45\x4F\x54\x00\x6D\x38\\\xC0\x00\x02\x01\x6D\x38\\EOT\0\0\0"[..];
+        let (_, (message, warnings)) = parse_message_verbose(data).unwrap();
+        let getserversresponse = match message {
+            Message::GetServersResponse(getserversresponse) => getserversresponse,
+            message => panic!("unexpected message {:?}", message),
+        };
+        assert_eq!(
+            getserversresponse.servers(),
+            &[
+                SocketAddrV4::new(Ipv4Addr::new(69, 79, 84, 0), 27960),
+                SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960),
+            ]
+        );
+        assert!(getserversresponse.eot());
+        assert!(getserversresponse.trailer().is_empty());
+        assert_eq!(warnings, vec![]);
+    }
+
+    #[test]
+    fn test_parse_message_verbose_tab_in_protocol_name() {
+        let data = &b"\xFF\xFF\xFF\xFFheartbeat Dark\tPlaces\n"[..];
+        let (_, (message, warnings)) = parse_message_verbose(data).unwrap();
+        assert_eq!(
+            message,
+            Message::Heartbeat(HeartbeatMessage::new(
+                ProtocolName::new(b"Dark\tPlaces".to_vec()).unwrap()
+            ))
+        );
+        assert_eq!(warnings, vec![]);
+
+        let data = &b"\xFF\xFF\xFF\xFFheartbeat\tDark\tPlaces\r\n"[..];
+        let (_, (_, warnings)) = parse_message_verbose(data).unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                ParseWarning::TabSeparator { offset: 13 },
+                ParseWarning::CarriageReturn { offset: 25 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_message_verbose_multiple_warnings() {
+        let data = &b"\xFF\xFF\xFF\xFF\\\x01\x02\x03\x04\x08\x00\\EOT"[..];
+        let (_, (message, warnings)) = parse_message_verbose(data).unwrap();
+        assert_eq!(message.command(), Command::GetServersResponse);
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::MissingCommand, ParseWarning::ShortEotPadding]
+        );
+    }
 }
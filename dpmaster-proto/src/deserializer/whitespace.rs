@@ -0,0 +1,120 @@
+//! whitespace combinators
+//!
+//! The [`Mode`] determines which bytes are accepted as whitespace, so the strict and lenient policy lives here.
+
+use super::Mode;
+use nom::bytes::complete::{take_while, take_while1, take_while_m_n};
+use nom::error::ParseError;
+use nom::IResult;
+
+/// Returns whether a byte is a space between fields in the given `mode`
+///
+/// * [`Mode::Strict`] only accepts `' '`
+/// * [`Mode::Lenient`] also accepts `'\t'`
+pub fn is_space(mode: Mode) -> impl Fn(u8) -> bool {
+    move |chr| match mode {
+        Mode::Strict => b' ' == chr,
+        Mode::Lenient => b' ' == chr || b'\t' == chr,
+    }
+}
+
+/// Returns whether a byte is part of a line ending in the given `mode`
+///
+/// * [`Mode::Strict`] only accepts `'\n'`
+/// * [`Mode::Lenient`] also accepts `'\r'`, i.e. `\r\n` line endings
+pub fn is_newline(mode: Mode) -> impl Fn(u8) -> bool {
+    move |chr| match mode {
+        Mode::Strict => b'\n' == chr,
+        Mode::Lenient => b'\n' == chr || b'\r' == chr,
+    }
+}
+
+/// Parser for exactly one space
+pub fn ws<'a, Error>(mode: Mode) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], &'a [u8], Error>
+where
+    Error: ParseError<&'a [u8]>,
+{
+    move |input| take_while_m_n(1, 1, is_space(mode))(input)
+}
+
+/// Parser for one or more spaces
+pub fn space1<'a, Error>(mode: Mode) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], &'a [u8], Error>
+where
+    Error: ParseError<&'a [u8]>,
+{
+    move |input| take_while1(is_space(mode))(input)
+}
+
+/// Parser for zero or more spaces
+pub fn spaces<'a, Error>(mode: Mode) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], &'a [u8], Error>
+where
+    Error: ParseError<&'a [u8]>,
+{
+    move |input| take_while(is_space(mode))(input)
+}
+
+/// Parser for zero or more line endings
+pub fn newlines<'a, Error>(mode: Mode) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], &'a [u8], Error>
+where
+    Error: ParseError<&'a [u8]>,
+{
+    move |input| take_while(is_newline(mode))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<'a> = IResult<&'a [u8], &'a [u8], ()>;
+
+    #[test]
+    fn test_ws() {
+        let result: Result = ws(Mode::Strict)(b"  x");
+        assert_eq!(result, Ok((&b" x"[..], &b" "[..])));
+
+        let result: Result = ws(Mode::Strict)(b"\tx");
+        assert!(result.is_err());
+
+        let result: Result = ws(Mode::Lenient)(b"\tx");
+        assert_eq!(result, Ok((&b"x"[..], &b"\t"[..])));
+    }
+
+    #[test]
+    fn test_space1() {
+        let result: Result = space1(Mode::Strict)(b"  x");
+        assert_eq!(result, Ok((&b"x"[..], &b"  "[..])));
+
+        let result: Result = space1(Mode::Strict)(b"x");
+        assert!(result.is_err());
+
+        let result: Result = space1(Mode::Strict)(b" \tx");
+        assert_eq!(result, Ok((&b"\tx"[..], &b" "[..])));
+
+        let result: Result = space1(Mode::Lenient)(b" \tx");
+        assert_eq!(result, Ok((&b"x"[..], &b" \t"[..])));
+    }
+
+    #[test]
+    fn test_spaces() {
+        let result: Result = spaces(Mode::Strict)(b"x");
+        assert_eq!(result, Ok((&b"x"[..], &b""[..])));
+
+        let result: Result = spaces(Mode::Strict)(b"\t x");
+        assert_eq!(result, Ok((&b"\t x"[..], &b""[..])));
+
+        let result: Result = spaces(Mode::Lenient)(b"\t x");
+        assert_eq!(result, Ok((&b"x"[..], &b"\t "[..])));
+    }
+
+    #[test]
+    fn test_newlines() {
+        let result: Result = newlines(Mode::Strict)(b"\n\nx");
+        assert_eq!(result, Ok((&b"x"[..], &b"\n\n"[..])));
+
+        let result: Result = newlines(Mode::Strict)(b"\r\n");
+        assert_eq!(result, Ok((&b"\r\n"[..], &b""[..])));
+
+        let result: Result = newlines(Mode::Lenient)(b"\r\n");
+        assert_eq!(result, Ok((&b""[..], &b"\r\n"[..])));
+    }
+}
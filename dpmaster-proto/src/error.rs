@@ -13,20 +13,73 @@ pub struct EmptyError(pub(crate) ());
 pub struct InvalidByteError(pub(crate) usize, pub(crate) Vec<u8>);
 
 /// Errors for [Challenge](crate::messages::Challenge)
+///
+/// The wrapped error is the [`source`](std::error::Error::source), so error reporters print the whole cause chain.
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
 pub enum InvalidChallengeError {
-    #[error(transparent)]
+    #[error("invalid challenge")]
     Empty(#[from] EmptyError),
-    #[error(transparent)]
+    #[error("invalid challenge")]
+    InvalidByte(#[from] InvalidByteError),
+}
+
+/// Errors for [InfoKey](crate::messages::InfoKey)
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum InvalidInfoKeyError {
+    #[error("invalid info key")]
+    InvalidByte(#[from] InvalidByteError),
+}
+
+/// Errors for [InfoValue](crate::messages::InfoValue)
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum InvalidInfoValueError {
+    #[error("invalid info value")]
     InvalidByte(#[from] InvalidByteError),
 }
 
+/// Errors for verifying the [Challenge](crate::messages::Challenge) of an [`infoResponse` message](crate::messages::InfoResponseMessage)
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum ChallengeVerificationError {
+    #[error("missing challenge")]
+    Missing,
+    #[error("challenge mismatch")]
+    Mismatch,
+    #[error("challenge length {len} exceeds maximum {max_len}")]
+    TooLong { len: usize, max_len: usize },
+}
+
+/// Errors for [ChallengeConfig](crate::flow::ChallengeConfig)
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum InvalidChallengeConfigError {
+    #[error("challenge length must not be zero")]
+    ZeroLength,
+    #[error("challenge length {len} exceeds maximum {max_len}")]
+    TooLong { len: usize, max_len: usize },
+    #[error("invalid challenge alphabet")]
+    InvalidAlphabet(#[from] InvalidChallengeError),
+}
+
+/// Errors for the typed accessors of [Info](crate::messages::Info)
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum InfoFieldError {
+    #[error("missing info key {0}")]
+    Missing(&'static str),
+    #[error("invalid value of info key {0}")]
+    Invalid(&'static str),
+}
+
 /// Possible crate errors
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum ProtocolError {
     /// Invalid [`crate::Challenge`]
     #[error(transparent)]
     InvalidChallenge(#[from] InvalidChallengeError),
+    /// Invalid [`crate::InfoKey`]
+    #[error(transparent)]
+    InvalidInfoKey(#[from] InvalidInfoKeyError),
+    /// Invalid [`crate::InfoValue`]
+    #[error(transparent)]
+    InvalidInfoValue(#[from] InvalidInfoValueError),
 
     /// Invalid [`crate::GameName`]
     #[error("Invalid game name ({byte} at {offset})")]
@@ -39,6 +92,24 @@ pub enum ProtocolError {
     /// In [`crate::GetServersResponseMessage`] or [`crate::GetServersExtResponseMessage`]
     #[error("Invalid EOT (no servers)")]
     InvalidEndOfTransmission,
+    /// Invalid message
+    ///
+    /// When converting bytes into a [`crate::messages::Message`]
+    #[error("Invalid message")]
+    InvalidMessage,
+    /// Unexpected message type
+    ///
+    /// When converting a [`crate::messages::Message`] into a concrete message type
+    #[error("Unexpected message ({found:?} instead of {expected:?})")]
+    UnexpectedMessage {
+        expected: crate::messages::Command,
+        found: crate::messages::Command,
+    },
+    /// Trailing bytes after a message
+    ///
+    /// When converting bytes into a [`crate::messages::Message`]
+    #[error("Trailing bytes after message ({len})")]
+    TrailingBytes { len: usize },
 }
 
 #[derive(Debug, PartialEq)]
@@ -52,8 +123,12 @@ impl<I> crate::deserializer::ParseError<I> for DeserializationError<I> {
         Self::Dpmaster(input, kind)
     }
 
-    fn append_dpmaster(_input: I, _kind: crate::deserializer::ErrorKind, other: Self) -> Self {
-        other
+    fn append_dpmaster(input: I, kind: crate::deserializer::ErrorKind, other: Self) -> Self {
+        // the innermost dpmaster-specific kind is more useful than any nom kind
+        match other {
+            Self::Nom(..) => Self::Dpmaster(input, kind),
+            Self::Dpmaster(..) => other,
+        }
     }
 }
 
@@ -80,3 +155,54 @@ impl<I, E> nom::error::FromExternalError<I, E> for DeserializationError<I> {
         Self::from_error_kind(input, kind)
     }
 }
+
+/// Converts into an [`InvalidData`](std::io::ErrorKind::InvalidData) I/O error, e.g. for `io::Error`-typed codecs
+///
+/// The `ProtocolError` is kept as the [`inner`](std::io::Error::get_ref) error, so its message is preserved.
+impl From<ProtocolError> for std::io::Error {
+    fn from(err: ProtocolError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::Challenge;
+    use std::error::Error as _;
+
+    fn chain(err: &dyn std::error::Error) -> Vec<String> {
+        let mut chain = vec![err.to_string()];
+        let mut source = err.source();
+        while let Some(err) = source {
+            chain.push(err.to_string());
+            source = err.source();
+        }
+        chain
+    }
+
+    #[test]
+    fn test_challenge_error_source_chain() {
+        let err = Challenge::new(Vec::new()).unwrap_err();
+        assert_eq!(err.source().unwrap().to_string(), "is empty");
+        assert_eq!(chain(&err), vec!["invalid challenge", "is empty"]);
+
+        let err = ProtocolError::from(Challenge::new(b"a\\b".to_vec()).unwrap_err());
+        assert_eq!(
+            chain(&err),
+            vec!["invalid challenge", "contains invalid byte 92 at 1"]
+        );
+    }
+
+    #[test]
+    fn test_protocol_error_into_io_error() {
+        let err = std::io::Error::from(ProtocolError::InvalidMessage);
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "Invalid message");
+        assert_eq!(
+            err.get_ref()
+                .and_then(|inner| inner.downcast_ref::<ProtocolError>()),
+            Some(&ProtocolError::InvalidMessage)
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Message flow helpers
+//!
+//! These implement the logic of the [message flows](crate#message-flows) on top of the [messages](crate::messages),
+//! but still without any I/O.
+
+use crate::error::{ChallengeVerificationError, InvalidChallengeConfigError};
+use crate::messages::{Challenge, GetInfoMessage, HeartbeatMessage, Info, InfoResponseMessage};
+
+/// Configuration of the challenges issued by a [`ChallengeFlow`]
+///
+/// The master server picks challenges of [`challenge_len`](ChallengeConfig::challenge_len) bytes from its
+/// [`alphabet`](ChallengeConfig::alphabet), and rejects echoed challenges longer than
+/// [`max_challenge_len`](ChallengeConfig::max_challenge_len) before even comparing them.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::flow::ChallengeConfig;
+/// let config = ChallengeConfig::new(8, 12, b"0123456789abcdef".to_vec())?;
+/// assert_eq!(config.challenge_len(), 8);
+/// # Ok::<(), dpmaster_proto::error::InvalidChallengeConfigError>(())
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ChallengeConfig {
+    len: usize,
+    max_len: usize,
+    alphabet: Vec<u8>,
+}
+
+impl ChallengeConfig {
+    /// Length of the challenges issued by the [default](ChallengeConfig::default) configuration
+    pub const DEFAULT_LEN: usize = 12;
+
+    /// Creates a new `ChallengeConfig`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`InvalidChallengeConfigError::ZeroLength`] if `len` is zero,
+    /// [`InvalidChallengeConfigError::TooLong`] if `len` exceeds `max_len`
+    /// and [`InvalidChallengeConfigError::InvalidAlphabet`] if `alphabet` is empty or contains bytes
+    /// not allowed in a [`Challenge`].
+    pub fn new(
+        len: usize,
+        max_len: usize,
+        alphabet: Vec<u8>,
+    ) -> Result<Self, InvalidChallengeConfigError> {
+        if len == 0 {
+            return Err(InvalidChallengeConfigError::ZeroLength);
+        }
+        if len > max_len {
+            return Err(InvalidChallengeConfigError::TooLong { len, max_len });
+        }
+        let alphabet = Challenge::new(alphabet)?[..].to_vec();
+        Ok(Self {
+            len,
+            max_len,
+            alphabet,
+        })
+    }
+
+    /// Returns the length of the issued challenges.
+    pub fn challenge_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the maximum accepted length of echoed challenges.
+    pub fn max_challenge_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Returns the bytes issued challenges are made of.
+    pub fn alphabet(&self) -> &[u8] {
+        &self.alphabet
+    }
+
+    /// Generates a new random `Challenge` according to this configuration.
+    ///
+    /// Uses the thread-local cryptographically secure RNG of the [`rand`](https://docs.rs/rand) crate.
+    #[cfg(feature = "rand")]
+    pub fn generate(&self) -> Challenge {
+        Challenge::random_from(&mut rand::thread_rng(), &self.alphabet, self.len)
+            .expect("alphabet and length are validated by ChallengeConfig::new")
+    }
+}
+
+impl Default for ChallengeConfig {
+    /// Challenges of [`DEFAULT_LEN`](ChallengeConfig::DEFAULT_LEN) bytes out of all bytes allowed in a [`Challenge`]
+    fn default() -> Self {
+        let alphabet = crate::messages::challenge_alphabet();
+        Self::new(Self::DEFAULT_LEN, Self::DEFAULT_LEN, alphabet).expect("default is valid")
+    }
+}
+
+/// Master server side of the [`heartbeat` message flow](crate#heartbeat-message-flow) for a single game server
+///
+/// ❶ On receiving a [`heartbeat` message](HeartbeatMessage) the master server [starts](ChallengeFlow::start) the flow
+/// with a new [`Challenge`](Challenge), which results in a [`getinfo` message](GetInfoMessage) to send to the game server.
+///
+/// ❷ On receiving the [`infoResponse` message](InfoResponseMessage) from the game server the master server [verifies](ChallengeFlow::verify)
+/// that it contains the same challenge, which authenticates the game server [`Info`](Info).
+///
+/// Generating an unpredictable challenge is up to the caller.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::flow::ChallengeFlow;
+/// # use dpmaster_proto::messages::{Challenge, HeartbeatMessage, Info, InfoKey, InfoResponseMessage, InfoValue, ProtocolName};
+/// let heartbeat = HeartbeatMessage::new(ProtocolName::default());
+/// let (flow, getinfo) = ChallengeFlow::start(&heartbeat, Challenge::new(*b"A_ch4Lleng3")?);
+/// assert_eq!(getinfo.challenge(), flow.challenge());
+///
+/// let mut info = Info::new();
+/// info.insert(InfoKey::new(*b"challenge")?, InfoValue::new(*b"A_ch4Lleng3")?);
+/// let inforesponse = InfoResponseMessage::new(info);
+/// assert!(flow.verify(&inforesponse).is_ok());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct ChallengeFlow {
+    challenge: Challenge,
+    max_len: usize,
+}
+
+impl ChallengeFlow {
+    /// Starts a new `ChallengeFlow` in response to the `heartbeat` with the given `challenge`.
+    ///
+    /// Returns the flow as well as the [`getinfo` message](GetInfoMessage) to send back to the game server.
+    pub fn start(_heartbeat: &HeartbeatMessage, challenge: Challenge) -> (Self, GetInfoMessage) {
+        let getinfo = GetInfoMessage::new(challenge.clone());
+        (
+            Self {
+                challenge,
+                max_len: usize::MAX,
+            },
+            getinfo,
+        )
+    }
+
+    /// Starts a new `ChallengeFlow` like [`start`](ChallengeFlow::start), but enforcing the lengths of `config`.
+    ///
+    /// Echoed challenges longer than [`ChallengeConfig::max_challenge_len`] are then rejected by [`verify`](ChallengeFlow::verify).
+    ///
+    /// # Errors
+    ///
+    /// Will return [`InvalidChallengeConfigError::TooLong`] if `challenge` itself exceeds the maximum length.
+    pub fn start_with_config(
+        heartbeat: &HeartbeatMessage,
+        challenge: Challenge,
+        config: &ChallengeConfig,
+    ) -> Result<(Self, GetInfoMessage), InvalidChallengeConfigError> {
+        if challenge.len() > config.max_len {
+            return Err(InvalidChallengeConfigError::TooLong {
+                len: challenge.len(),
+                max_len: config.max_len,
+            });
+        }
+        let (mut flow, getinfo) = Self::start(heartbeat, challenge);
+        flow.max_len = config.max_len;
+        Ok((flow, getinfo))
+    }
+
+    /// Starts a new `ChallengeFlow` with a challenge [generated](ChallengeConfig::generate) according to `config`.
+    #[cfg(feature = "rand")]
+    pub fn start_random(
+        heartbeat: &HeartbeatMessage,
+        config: &ChallengeConfig,
+    ) -> (Self, GetInfoMessage) {
+        Self::start_with_config(heartbeat, config.generate(), config)
+            .expect("generated challenge is not too long")
+    }
+
+    /// Returns the `Challenge` issued by this flow.
+    pub fn challenge(&self) -> &Challenge {
+        &self.challenge
+    }
+
+    /// Verifies that the `inforesponse` carries the challenge issued by this flow.
+    ///
+    /// The challenges are compared in constant time, see [`Challenge::ct_eq`].
+    ///
+    /// Returns the authenticated `Info` of the game server.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`ChallengeVerificationError::Missing`] if the `Info` does not contain a `challenge` key
+    /// and [`ChallengeVerificationError::Mismatch`] if it contains a different challenge,
+    /// e.g. because the `infoResponse` was spoofed.
+    /// With a [`ChallengeConfig`] it will return [`ChallengeVerificationError::TooLong`] if the echoed challenge
+    /// exceeds the configured maximum length.
+    pub fn verify<'a>(
+        &self,
+        inforesponse: &'a InfoResponseMessage,
+    ) -> Result<&'a Info, ChallengeVerificationError> {
+        let info = inforesponse.info();
+        let (_, challenge) = info
+            .iter()
+            .find(|(key, _)| &key[..] == b"challenge")
+            .ok_or(ChallengeVerificationError::Missing)?;
+        if challenge.len() > self.max_len {
+            return Err(ChallengeVerificationError::TooLong {
+                len: challenge.len(),
+                max_len: self.max_len,
+            });
+        }
+
+        if crate::messages::ct_eq(&challenge[..], &self.challenge[..]) {
+            Ok(info)
+        } else {
+            Err(ChallengeVerificationError::Mismatch)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{InfoKey, InfoValue, ProtocolName};
+
+    fn inforesponse(challenge: Option<&[u8]>) -> InfoResponseMessage {
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
+            InfoValue::new(b"8".to_vec()).unwrap(),
+        );
+        if let Some(challenge) = challenge {
+            info.insert(
+                InfoKey::new(b"challenge".to_vec()).unwrap(),
+                InfoValue::new(challenge.to_vec()).unwrap(),
+            );
+        }
+        InfoResponseMessage::new(info)
+    }
+
+    fn start() -> (ChallengeFlow, GetInfoMessage) {
+        ChallengeFlow::start(
+            &HeartbeatMessage::new(ProtocolName::default()),
+            Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_challenge_flow_start() {
+        let (flow, getinfo) = start();
+        assert_eq!(
+            getinfo,
+            GetInfoMessage::new(Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap())
+        );
+        assert_eq!(flow.challenge(), getinfo.challenge());
+    }
+
+    #[test]
+    fn test_challenge_flow_verify() {
+        let (flow, _) = start();
+        let inforesponse = inforesponse(Some(b"A_ch4Lleng3"));
+        assert_eq!(flow.verify(&inforesponse), Ok(inforesponse.info()));
+    }
+
+    #[test]
+    fn test_challenge_flow_verify_mismatch() {
+        let (flow, _) = start();
+        let inforesponse = inforesponse(Some(b"sp00fed"));
+        assert_eq!(
+            flow.verify(&inforesponse),
+            Err(ChallengeVerificationError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn test_challenge_flow_verify_missing() {
+        let (flow, _) = start();
+        let inforesponse = inforesponse(None);
+        assert_eq!(
+            flow.verify(&inforesponse),
+            Err(ChallengeVerificationError::Missing)
+        );
+    }
+
+    #[test]
+    fn test_challenge_config_new() {
+        assert_eq!(
+            ChallengeConfig::new(0, 12, b"abc".to_vec()),
+            Err(InvalidChallengeConfigError::ZeroLength)
+        );
+        assert_eq!(
+            ChallengeConfig::new(13, 12, b"abc".to_vec()),
+            Err(InvalidChallengeConfigError::TooLong {
+                len: 13,
+                max_len: 12
+            })
+        );
+        assert!(matches!(
+            ChallengeConfig::new(8, 12, b"".to_vec()),
+            Err(InvalidChallengeConfigError::InvalidAlphabet(_))
+        ));
+        assert!(matches!(
+            ChallengeConfig::new(8, 12, b"ab\\c".to_vec()),
+            Err(InvalidChallengeConfigError::InvalidAlphabet(_))
+        ));
+
+        let config = ChallengeConfig::new(8, 12, b"abc".to_vec()).unwrap();
+        assert_eq!(config.challenge_len(), 8);
+        assert_eq!(config.max_challenge_len(), 12);
+        assert_eq!(config.alphabet(), b"abc");
+    }
+
+    #[test]
+    fn test_challenge_config_default() {
+        let config = ChallengeConfig::default();
+        assert_eq!(config.challenge_len(), ChallengeConfig::DEFAULT_LEN);
+        assert!(config.alphabet().contains(&b'A'));
+        assert!(!config.alphabet().contains(&b'\\'));
+    }
+
+    #[test]
+    fn test_challenge_flow_with_config() {
+        let config = ChallengeConfig::new(11, 11, b"A_ch4Lleng3".to_vec()).unwrap();
+        let heartbeat = HeartbeatMessage::new(ProtocolName::default());
+
+        assert_eq!(
+            ChallengeFlow::start_with_config(
+                &heartbeat,
+                Challenge::new(b"A_ch4Lleng3_".to_vec()).unwrap(),
+                &config
+            ),
+            Err(InvalidChallengeConfigError::TooLong {
+                len: 12,
+                max_len: 11
+            })
+        );
+
+        let (flow, getinfo) = ChallengeFlow::start_with_config(
+            &heartbeat,
+            Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap(),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(flow.challenge(), getinfo.challenge());
+
+        let echoed = inforesponse(Some(b"A_ch4Lleng3"));
+        assert_eq!(flow.verify(&echoed), Ok(echoed.info()));
+
+        let tampered = inforesponse(Some(b"A_ch4Lleng3A_ch4Lleng3"));
+        assert_eq!(
+            flow.verify(&tampered),
+            Err(ChallengeVerificationError::TooLong {
+                len: 22,
+                max_len: 11
+            })
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_challenge_flow_start_random() {
+        let config = ChallengeConfig::new(9, 12, b"xyz".to_vec()).unwrap();
+        let (flow, getinfo) =
+            ChallengeFlow::start_random(&HeartbeatMessage::new(ProtocolName::default()), &config);
+        assert_eq!(flow.challenge(), getinfo.challenge());
+        assert_eq!(flow.challenge().len(), 9);
+        assert!(flow.challenge()[..]
+            .iter()
+            .all(|byte| b"xyz".contains(byte)));
+
+        let echoed = inforesponse(Some(&flow.challenge()[..]));
+        assert_eq!(flow.verify(&echoed), Ok(echoed.info()));
+    }
+}
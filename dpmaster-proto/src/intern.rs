@@ -0,0 +1,168 @@
+//! Interning of repeated names
+//!
+//! A master server parses lots of messages which contain only few distinct [`ProtocolName`](crate::messages::ProtocolName)s
+//! and [`GameName`](crate::messages::GameName)s, but the owned message types allocate them anew for each message.\
+//! The [`Interner`] instead shares a single allocation per distinct name, e.g. for [`heartbeat_message_interned`](crate::deserializer::heartbeat_message_interned).
+
+use crate::messages::GameName;
+use crate::Result;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Interner for byte strings
+///
+/// Returns the same shared allocation for equal byte strings.\
+/// The interner is caller-provided and never evicts, so its lifetime bounds its memory usage.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::intern::Interner;
+/// # use std::sync::Arc;
+/// let mut interner = Interner::new();
+/// let first = interner.intern(b"Nexuiz");
+/// let second = interner.intern(b"Nexuiz");
+/// assert!(Arc::ptr_eq(&first, &second));
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct Interner {
+    names: HashSet<Arc<[u8]>>,
+}
+
+impl Interner {
+    /// Creates a new, empty `Interner`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the shared allocation for `bytes`.
+    ///
+    /// Only allocates if `bytes` have not been interned before.
+    pub fn intern(&mut self, bytes: &[u8]) -> Arc<[u8]> {
+        match self.names.get(bytes) {
+            Some(name) => Arc::clone(name),
+            None => {
+                let name: Arc<[u8]> = bytes.into();
+                self.names.insert(Arc::clone(&name));
+                name
+            }
+        }
+    }
+
+    /// Returns the interned `SharedProtocolName` for `bytes`.
+    pub fn protocol_name(&mut self, bytes: &[u8]) -> SharedProtocolName {
+        SharedProtocolName(self.intern(bytes))
+    }
+
+    /// Returns the interned `SharedGameName` for `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`GameName::new`].
+    pub fn game_name(&mut self, bytes: &[u8]) -> Result<SharedGameName> {
+        GameName::validate(bytes)?;
+        Ok(SharedGameName(self.intern(bytes)))
+    }
+
+    /// Returns the number of distinct byte strings in this `Interner`.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns `true` if this `Interner` contains no byte strings.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Interned [`ProtocolName`](crate::messages::ProtocolName)
+///
+/// Cheap to clone, since clones share the allocation.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct SharedProtocolName(Arc<[u8]>);
+
+impl SharedProtocolName {
+    /// Returns the number of bytes in this `SharedProtocolName`.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if this `SharedProtocolName` contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<I: std::slice::SliceIndex<[u8]>> std::ops::Index<I> for SharedProtocolName {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        std::ops::Index::index(&*self.0, index)
+    }
+}
+
+/// Interned [`GameName`](crate::messages::GameName)
+///
+/// Cheap to clone, since clones share the allocation.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct SharedGameName(Arc<[u8]>);
+
+impl SharedGameName {
+    /// Returns the number of bytes in this `SharedGameName`.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if this `SharedGameName` contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<I: std::slice::SliceIndex<[u8]>> std::ops::Index<I> for SharedGameName {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        std::ops::Index::index(&*self.0, index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProtocolError;
+
+    #[test]
+    fn test_intern_shares_allocation() {
+        let mut interner = Interner::new();
+        assert!(interner.is_empty());
+
+        let names: Vec<_> = (0..1000).map(|_| interner.intern(b"DarkPlaces")).collect();
+        assert!(names.iter().all(|name| Arc::ptr_eq(name, &names[0])));
+        assert_eq!(interner.len(), 1);
+
+        let other = interner.intern(b"QuakeArena-1");
+        assert!(!Arc::ptr_eq(&other, &names[0]));
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn test_interner_game_name() {
+        let mut interner = Interner::new();
+        let first = interner.game_name(b"Nexuiz").unwrap();
+        let second = interner.game_name(b"Nexuiz").unwrap();
+        assert!(Arc::ptr_eq(&first.0, &second.0));
+        assert_eq!(&first[..], b"Nexuiz");
+
+        assert_eq!(
+            interner.game_name(b"invalid example"),
+            Err(ProtocolError::InvalidGameName {
+                byte: b' ',
+                offset: 7
+            })
+        );
+        assert_eq!(interner.len(), 1);
+    }
+}
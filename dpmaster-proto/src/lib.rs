@@ -57,12 +57,17 @@
 //!
 //! This crate implements the `dpmaster` protocol wire format, i.e. it allows to serialize and deserialize protocol messages as bytes to and from UDP packets.\
 //! It does however not contain logic (["sans I/O"](https://sans-io.readthedocs.io/)), e.g. to implement a master server, game server or game client.\
+//! The [`flow`](crate::flow) module contains some I/O-free helpers for the message flows though.
 //!
 //! The `dpmaster-codec` crate implements Tokio codecs on top of this protocol crate.\
+//! The `dpmaster` crate re-exports both of them as a single dependency.\
 //! The `dpmaster-game-client-bin` crate implements a "game client" on top of a codec in form of a command-line-interface to query a master server for game servers.
 
 pub mod deserializer;
 pub mod error;
+pub mod flow;
+#[cfg(feature = "intern")]
+pub mod intern;
 pub mod messages;
 pub mod serializer;
 
@@ -72,6 +77,7 @@ pub use messages::{
     ProtocolName,
 };
 
+pub use crate::deserializer::ErrorKind;
 pub use crate::error::ProtocolError;
 /// [std::result::Result] alias with [ProtocolError] as `Err`
 pub type Result<T, E = ProtocolError> = std::result::Result<T, E>;
@@ -84,10 +90,11 @@ mod tests {
     use super::messages::{
         Challenge, FilterOptions, GameName, GameType, GetInfoMessage, GetServersMessage,
         HeartbeatMessage, Info, InfoKey, InfoResponseMessage, InfoValue, ProtocolName,
+        ProtocolNumber,
     };
     use super::serializer::{
-        gen_getinfo_message, gen_getservers_message, gen_heartbeat_message,
-        gen_inforesponse_message,
+        gen_getinfo_message, gen_getinfo_message_with_trailer, gen_getservers_message,
+        gen_heartbeat_message, gen_inforesponse_message,
     };
     use cookie_factory::gen_simple;
     use std::io::Cursor;
@@ -97,6 +104,52 @@ mod tests {
         assert_eq!(2 + 2, 4);
     }
 
+    #[test]
+    fn test_rewrite_getservers_message() {
+        let (_, mut message) =
+            getservers_message(b"\xFF\xFF\xFF\xFFgetservers 67 gametype=0 empty full").unwrap();
+
+        message.set_game_name(Some(GameName::new(b"Quake3Arena".to_vec()).unwrap()));
+        message.set_protocol_number(ProtocolNumber::new(68).unwrap());
+        message.filter_options_mut().set_empty(false);
+        message.filter_options_mut().set_gametype(None);
+
+        let mut buffer = [0u8; 512];
+        let cursor = Cursor::new(&mut buffer[..]);
+        let cursor = gen_simple(gen_getservers_message(&message), cursor).unwrap();
+        let size = cursor.position() as usize;
+        let buffer = cursor.into_inner();
+
+        assert_eq!(
+            &buffer[..size],
+            &b"\xFF\xFF\xFF\xFFgetservers Quake3Arena 68 full"[..]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_getinfo_message() {
+        let (_, mut message) = getinfo_message(b"\xFF\xFF\xFF\xFFgetinfo A_ch4Lleng3").unwrap();
+
+        message.set_challenge(Challenge::new(b"n3w_ch4Lleng3".to_vec()).unwrap());
+
+        let mut buffer = [0u8; 512];
+        let cursor = Cursor::new(&mut buffer[..]);
+        let cursor = gen_simple(gen_getinfo_message(&message), cursor).unwrap();
+        let size = cursor.position() as usize;
+        let buffer = cursor.into_inner();
+
+        assert_eq!(
+            &buffer[..size],
+            &b"\xFF\xFF\xFF\xFFgetinfo n3w_ch4Lleng3"[..]
+        );
+
+        *message.challenge_mut() = Challenge::new(b"0th3r".to_vec()).unwrap();
+        assert_eq!(
+            message,
+            GetInfoMessage::new(Challenge::new(b"0th3r".to_vec()).unwrap())
+        );
+    }
+
     macro_rules! roundtrip_message_test {
         (
             $name:ident {
@@ -153,6 +206,10 @@ mod tests {
         message: HeartbeatMessage::new(ProtocolName::new(b"EnemyTerritory-1".to_vec()).unwrap(),)
     });
 
+    roundtrip_heartbeat_message_test!(test_roundtrip_heartbeat_message_spaces {
+        message: HeartbeatMessage::new(ProtocolName::new(b"My Game".to_vec()).unwrap(),)
+    });
+
     macro_rules! roundtrip_getinfo_message_test {
         (
         $name:ident {
@@ -171,6 +228,18 @@ mod tests {
         message: GetInfoMessage::new(Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap(),)
     });
 
+    roundtrip_message_test!(test_roundtrip_getinfo_message_trailing_newline {
+        message: GetInfoMessage::new(Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap(),),
+        serializer: |message| gen_getinfo_message_with_trailer(message, b'\n'),
+        deserializer: getinfo_message,
+    });
+
+    roundtrip_message_test!(test_roundtrip_getinfo_message_trailing_space {
+        message: GetInfoMessage::new(Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap(),),
+        serializer: |message| gen_getinfo_message_with_trailer(message, b' '),
+        deserializer: getinfo_message,
+    });
+
     macro_rules! roundtrip_inforesponse_message_test {
         (
         $name:ident {
@@ -200,6 +269,21 @@ mod tests {
         })
     });
 
+    roundtrip_inforesponse_message_test!(test_roundtrip_inforesponse_message_empty_value {
+        message: InfoResponseMessage::new({
+            let mut info = Info::new();
+            info.insert(
+                InfoKey::new(b"pure".to_vec()).unwrap(),
+                InfoValue::new(b"".to_vec()).unwrap(),
+            );
+            info.insert(
+                InfoKey::new(b"g_needpass".to_vec()).unwrap(),
+                InfoValue::new(b"0".to_vec()).unwrap(),
+            );
+            info
+        })
+    });
+
     macro_rules! roundtrip_getservers_message_test {
         (
         $name:ident {
@@ -217,19 +301,23 @@ mod tests {
     roundtrip_getservers_message_test!(test_roundtrip_getservers_message_q3a {
         message: GetServersMessage::new(
             None,
-            67,
+            ProtocolNumber::new(67).unwrap(),
             FilterOptions::new(Some(GameType::new(b"0".to_vec()).unwrap()), true, true)
         )
     });
 
     roundtrip_getservers_message_test!(test_roundtrip_getservers_message_woet {
-        message: GetServersMessage::new(None, 84, FilterOptions::new(None, false, false))
+        message: GetServersMessage::new(
+            None,
+            ProtocolNumber::new(84).unwrap(),
+            FilterOptions::new(None, false, false)
+        )
     });
 
     roundtrip_getservers_message_test!(test_roundtrip_getservers_message_nexuiz {
         message: GetServersMessage::new(
             Some(GameName::new(b"Nexuiz".to_vec()).unwrap()),
-            3,
+            ProtocolNumber::new(3).unwrap(),
             FilterOptions::new(None, false, false)
         )
     });
@@ -237,7 +325,7 @@ mod tests {
     roundtrip_getservers_message_test!(test_roundtrip_getservers_message_qfusion {
         message: GetServersMessage::new(
             Some(GameName::new(b"qfusion".to_vec()).unwrap()),
-            39,
+            ProtocolNumber::new(39).unwrap(),
             FilterOptions::new(None, false, true)
         )
     });
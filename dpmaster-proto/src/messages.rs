@@ -15,15 +15,297 @@
 //! 1. [`getserversExt`](GetServersExtMessage)
 //! 2. [`getserversExtResponse`](GetServersExtResponseMessage)
 
-use crate::error::{EmptyError, InvalidByteError, InvalidChallengeError};
+use crate::error::{
+    EmptyError, InfoFieldError, InvalidByteError, InvalidChallengeError, InvalidInfoKeyError,
+    InvalidInfoValueError,
+};
 use crate::{ProtocolError, Result};
 
-use memchr::memchr2;
+/// Command token of a message
+///
+/// Follows the message prefix and identifies the type of the message.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Command {
+    /// `heartbeat`, see [`HeartbeatMessage`]
+    Heartbeat,
+    /// `getinfo`, see [`GetInfoMessage`]
+    GetInfo,
+    /// `infoResponse`, see [`InfoResponseMessage`]
+    InfoResponse,
+    /// `getservers`, see [`GetServersMessage`]
+    GetServers,
+    /// `getserversResponse`, see [`GetServersResponseMessage`]
+    GetServersResponse,
+    /// `getserversExt`, see [`GetServersExtMessage`]
+    GetServersExt,
+    /// `getserversExtResponse`, see [`GetServersExtResponseMessage`]
+    GetServersExtResponse,
+    /// `getstatus`
+    GetStatus,
+    /// `statusResponse`
+    StatusResponse,
+    /// `print`, see [`PrintMessage`]
+    Print,
+}
+
+impl Command {
+    /// Returns the token bytes of this command.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::Command;
+    /// assert_eq!(Command::GetServers.as_bytes(), b"getservers");
+    /// ```
+    pub fn as_bytes(self) -> &'static [u8] {
+        match self {
+            Self::Heartbeat => b"heartbeat",
+            Self::GetInfo => b"getinfo",
+            Self::InfoResponse => b"infoResponse",
+            Self::GetServers => b"getservers",
+            Self::GetServersResponse => b"getserversResponse",
+            Self::GetServersExt => b"getserversExt",
+            Self::GetServersExtResponse => b"getserversExtResponse",
+            Self::GetStatus => b"getstatus",
+            Self::StatusResponse => b"statusResponse",
+            Self::Print => b"print",
+        }
+    }
+
+    /// Returns the command for the exact token `bytes`, if it is known.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::Command;
+    /// assert_eq!(Command::from_bytes(b"getservers"), Some(Command::GetServers));
+    /// assert_eq!(Command::from_bytes(b"hurz"), None);
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            b"heartbeat" => Some(Self::Heartbeat),
+            b"getinfo" => Some(Self::GetInfo),
+            b"infoResponse" => Some(Self::InfoResponse),
+            b"getservers" => Some(Self::GetServers),
+            b"getserversResponse" => Some(Self::GetServersResponse),
+            b"getserversExt" => Some(Self::GetServersExt),
+            b"getserversExtResponse" => Some(Self::GetServersExtResponse),
+            b"getstatus" => Some(Self::GetStatus),
+            b"statusResponse" => Some(Self::StatusResponse),
+            b"print" => Some(Self::Print),
+            _ => None,
+        }
+    }
+}
+
+/// Length of the `\xFF\xFF\xFF\xFF` message prefix in bytes
+pub const MESSAGE_PREFIX_LEN: usize = 4;
+
+/// Length of an IPv4 server entry in a `getserversResponse` or `getserversExtResponse` message in bytes
+///
+/// The `\` separator, the 4 octets of the IPv4 address and the 2 octets of the port.
+pub const IPV4_ENTRY_LEN: usize = 1 + 4 + 2;
+
+/// Length of an IPv6 server entry in a `getserversExtResponse` message in bytes
+///
+/// The `/` separator, the 16 octets of the IPv6 address and the 2 octets of the port.
+pub const IPV6_ENTRY_LEN: usize = 1 + 16 + 2;
+
+/// Length of the `\EOT\0\0\0` end-of-transmission marker in bytes
+pub const EOT_LEN: usize = 7;
+
+/// Any message
+///
+/// Returned by parsers which accept more than one message type, e.g. [`parse_message`](crate::deserializer::parse_message).
+#[derive(Debug, PartialEq, Eq)]
+pub enum Message {
+    /// [`heartbeat` message](HeartbeatMessage)
+    Heartbeat(HeartbeatMessage),
+    /// [`getinfo` message](GetInfoMessage)
+    GetInfo(GetInfoMessage),
+    /// [`infoResponse` message](InfoResponseMessage)
+    InfoResponse(InfoResponseMessage),
+    /// [`getservers` message](GetServersMessage)
+    GetServers(GetServersMessage),
+    /// [`getserversResponse` message](GetServersResponseMessage)
+    GetServersResponse(GetServersResponseMessage),
+    /// [`getserversExt` message](GetServersExtMessage)
+    GetServersExt(GetServersExtMessage),
+    /// [`print` message](PrintMessage)
+    Print(PrintMessage),
+}
+
+impl Message {
+    /// Returns the [`Command`] of this message.
+    pub fn command(&self) -> Command {
+        match self {
+            Self::Heartbeat(_) => Command::Heartbeat,
+            Self::GetInfo(_) => Command::GetInfo,
+            Self::InfoResponse(_) => Command::InfoResponse,
+            Self::GetServers(_) => Command::GetServers,
+            Self::GetServersResponse(_) => Command::GetServersResponse,
+            Self::GetServersExt(_) => Command::GetServersExt,
+            Self::Print(_) => Command::Print,
+        }
+    }
+}
+
+/// Summarizes the message for logs, e.g. `getservers(Nexuiz, proto=3, empty)` or `getserversResponse(42 servers, eot)`.
+///
+/// Bytes which are not valid UTF-8 are replaced, so this is not suitable for serialization.
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lossy = String::from_utf8_lossy;
+        let command = lossy(self.command().as_bytes());
+        match self {
+            Self::Heartbeat(msg) => write!(f, "{}({})", command, lossy(&msg.protocol_name()[..])),
+            Self::GetInfo(msg) => write!(f, "{}({})", command, lossy(&msg.challenge()[..])),
+            Self::InfoResponse(msg) => {
+                write!(f, "{}({} keys)", command, msg.info().iter().len())
+            }
+            Self::GetServers(msg) => {
+                write!(f, "{}(", command)?;
+                if let Some(game_name) = msg.game_name() {
+                    write!(f, "{}, ", lossy(&game_name[..]))?;
+                }
+                write!(f, "proto={}", msg.protocol_number())?;
+                let options = msg.filter_options();
+                fmt_filter_options(f, options.gametype(), options.empty(), options.full())?;
+                write!(f, ")")
+            }
+            Self::GetServersResponse(msg) => {
+                write!(f, "{}({} servers", command, msg.servers().len())?;
+                if msg.eot() {
+                    write!(f, ", eot")?;
+                }
+                write!(f, ")")
+            }
+            Self::GetServersExt(msg) => {
+                write!(
+                    f,
+                    "{}({}, proto={}",
+                    command,
+                    lossy(&msg.game_name()[..]),
+                    msg.protocol_number()
+                )?;
+                let options = msg.filter_options();
+                fmt_filter_options(f, options.gametype(), options.empty(), options.full())?;
+                if options.ipv4() {
+                    write!(f, ", ipv4")?;
+                }
+                if options.ipv6() {
+                    write!(f, ", ipv6")?;
+                }
+                write!(f, ")")
+            }
+            Self::Print(msg) => write!(f, "{}({:?})", command, lossy(msg.text())),
+        }
+    }
+}
+
+fn fmt_filter_options(
+    f: &mut std::fmt::Formatter<'_>,
+    gametype: Option<&GameType>,
+    empty: bool,
+    full: bool,
+) -> std::fmt::Result {
+    if let Some(gametype) = gametype {
+        write!(f, ", gametype={}", String::from_utf8_lossy(&gametype[..]))?;
+    }
+    if empty {
+        write!(f, ", empty")?;
+    }
+    if full {
+        write!(f, ", full")?;
+    }
+    Ok(())
+}
+
+impl std::convert::TryFrom<&[u8]> for Message {
+    type Error = ProtocolError;
+
+    /// Parses a whole datagram with [`parse_message`](crate::deserializer::parse_message).
+    ///
+    /// # Errors
+    ///
+    /// Will return [`ProtocolError::InvalidMessage`] if `value` does not start with a valid message
+    /// and [`ProtocolError::TrailingBytes`] if there are bytes left after the message.
+    fn try_from(value: &[u8]) -> Result<Self> {
+        let (rest, message) =
+            crate::deserializer::parse_message(value).map_err(|_| ProtocolError::InvalidMessage)?;
+        if rest.is_empty() {
+            Ok(message)
+        } else {
+            Err(ProtocolError::TrailingBytes { len: rest.len() })
+        }
+    }
+}
+
+macro_rules! impl_try_from_message {
+    ($($variant:ident($message:ty)),* $(,)?) => {
+        $(
+            /// Downcasts a [`Message`] of the expected type.
+            ///
+            /// # Errors
+            ///
+            /// Will return [`ProtocolError::UnexpectedMessage`] if the message is of another type.
+            impl std::convert::TryFrom<Message> for $message {
+                type Error = ProtocolError;
+
+                fn try_from(message: Message) -> Result<Self> {
+                    match message {
+                        Message::$variant(message) => Ok(message),
+                        other => Err(ProtocolError::UnexpectedMessage {
+                            expected: Command::$variant,
+                            found: other.command(),
+                        }),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_message!(
+    Heartbeat(HeartbeatMessage),
+    GetInfo(GetInfoMessage),
+    InfoResponse(InfoResponseMessage),
+    GetServers(GetServersMessage),
+    GetServersResponse(GetServersResponseMessage),
+    GetServersExt(GetServersExtMessage),
+    Print(PrintMessage),
+);
+
+/// Compares `a` and `b` in constant time, i.e. independent of the position of the first differing byte.
+///
+/// Only the length of the inputs is leaked.
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    #[cfg(feature = "subtle")]
+    {
+        subtle::ConstantTimeEq::ct_eq(a, b).into()
+    }
+    #[cfg(not(feature = "subtle"))]
+    {
+        // best effort, the optimizer could in theory short-circuit this
+        a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
 
 fn is_ascii_printable(chr: u8) -> bool {
     chr >= 33 && chr <= 126
 }
 
+fn is_challenge_byte(chr: u8) -> bool {
+    is_ascii_printable(chr) && ![b'\\', b'/', b';', b'"', b'%'].contains(&chr)
+}
+
+/// All bytes allowed in a [`Challenge`]
+pub(crate) fn challenge_alphabet() -> Vec<u8> {
+    (0..=u8::MAX)
+        .filter(|&byte| is_challenge_byte(byte))
+        .collect()
+}
+
 /// "Password" to authenticate messages
 ///
 /// Contained in a [`getinfo` message](GetInfoMessage) and [`infoResponse` message](InfoResponseMessage).
@@ -31,7 +313,7 @@ fn is_ascii_printable(chr: u8) -> bool {
 /// The dpmaster protocol uses [UDP](https://en.wikipedia.org/wiki/User_Datagram_Protocol) which is spoofable so,
 /// to authenticate datagrams and prevent denial-of-service in the ([`heartbeat`](HeartbeatMessage) →) [`getinfo`](GetInfoMessage) → [`infoResponse`](InfoResponseMessage) chain,
 /// a "password" is used that should only be known to the game server and the master server.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Challenge(Vec<u8>);
 
 impl Challenge {
@@ -71,13 +353,110 @@ impl Challenge {
         }
 
         for (offset, byte) in bytes.iter().copied().enumerate() {
-            if !is_ascii_printable(byte) || [b'\\', b'/', b';', b'"', b'%'].contains(&byte) {
+            if !is_challenge_byte(byte) {
                 return Err(InvalidByteError(offset, bytes))?;
             }
         }
 
         Ok(Self(bytes))
     }
+
+    /// Creates a new random `Challenge` of `len` bytes.
+    ///
+    /// Uses the cryptographically secure [`rand::thread_rng`], so the challenge is unpredictable for an attacker.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::Challenge;
+    /// let challenge = Challenge::random(12)?;
+    /// assert_eq!(challenge.len(), 12);
+    /// # Ok::<(), dpmaster_proto::error::InvalidChallengeError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [EmptyError](crate::error::EmptyError) if `len` is `0`.
+    #[cfg(feature = "rand")]
+    pub fn random(len: usize) -> Result<Self, InvalidChallengeError> {
+        Self::random_from(&mut rand::thread_rng(), &challenge_alphabet(), len)
+    }
+
+    /// Creates a new `Challenge` of `len` bytes deterministically derived from `seed`.
+    ///
+    /// The same `seed` yields the same challenge, e.g. for reproducible tests.
+    /// The challenge is predictable though, so use [`random`](Challenge::random) for actual game servers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::Challenge;
+    /// assert_eq!(Challenge::from_seed(42, 12)?, Challenge::from_seed(42, 12)?);
+    /// # Ok::<(), dpmaster_proto::error::InvalidChallengeError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [EmptyError](crate::error::EmptyError) if `len` is `0`.
+    #[cfg(feature = "rand")]
+    pub fn from_seed(seed: u64, len: usize) -> Result<Self, InvalidChallengeError> {
+        use rand::SeedableRng;
+
+        Self::random_from(
+            &mut rand::rngs::StdRng::seed_from_u64(seed),
+            &challenge_alphabet(),
+            len,
+        )
+    }
+
+    /// Creates a new `Challenge` of `len` bytes chosen from `alphabet` with `rng`.
+    ///
+    /// Like [`Challenge::new`], fails for invalid bytes in `alphabet`, and for an empty challenge
+    /// because either `len` is `0` or `alphabet` is empty.
+    #[cfg(feature = "rand")]
+    pub(crate) fn random_from<R: rand::Rng>(
+        rng: &mut R,
+        alphabet: &[u8],
+        len: usize,
+    ) -> Result<Self, InvalidChallengeError> {
+        use rand::seq::SliceRandom;
+
+        let bytes: Option<Vec<u8>> = (0..len).map(|_| alphabet.choose(rng).copied()).collect();
+        Self::new(bytes.unwrap_or_default())
+    }
+
+    /// Compares this `Challenge` with `other` in constant time.
+    ///
+    /// Like `==`, but without leaking the number of matching leading bytes via the time it takes.
+    /// Otherwise an attacker spoofing `infoResponse` messages could measure the verification of guessed challenges
+    /// to learn a challenge byte by byte. Network jitter makes this hard in practice, but the comparison is cheap.\
+    /// Only the length of the challenges is leaked.
+    ///
+    /// Uses the [`subtle`](https://docs.rs/subtle) crate with the `subtle` feature enabled,
+    /// and a best-effort implementation otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::Challenge;
+    /// let challenge = Challenge::new(*b"A_ch4Lleng3")?;
+    /// assert!(challenge.ct_eq(&Challenge::new(*b"A_ch4Lleng3")?));
+    /// assert!(!challenge.ct_eq(&Challenge::new(*b"sp00fed")?));
+    /// # Ok::<(), dpmaster_proto::error::InvalidChallengeError>(())
+    /// ```
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        ct_eq(&self.0, &other.0)
+    }
+
+    /// Returns the number of bytes in this `Challenge`.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if this `Challenge` contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl<I: std::slice::SliceIndex<[u8]>> std::ops::Index<I> for Challenge {
@@ -105,10 +484,34 @@ impl GetInfoMessage {
         Self { challenge }
     }
 
+    /// Creates a new `GetInfoMessage` for a new [random](Challenge::random) `Challenge` of `len` bytes.
+    ///
+    /// Returns the challenge as well, for the master server to remember it until the `infoResponse`.
+    /// See [`ChallengeFlow`](crate::flow::ChallengeFlow) to do so.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Challenge::random`].
+    #[cfg(feature = "rand")]
+    pub fn new_random(len: usize) -> Result<(Self, Challenge), InvalidChallengeError> {
+        let challenge = Challenge::random(len)?;
+        Ok((Self::new(challenge.clone()), challenge))
+    }
+
     /// Returns the `Challenge` contained in this message.
     pub fn challenge(&self) -> &Challenge {
         &self.challenge
     }
+
+    /// Returns the mutable `Challenge` contained in this message.
+    pub fn challenge_mut(&mut self) -> &mut Challenge {
+        &mut self.challenge
+    }
+
+    /// Sets the `Challenge` contained in this message, e.g. to re-challenge a game server.
+    pub fn set_challenge(&mut self, challenge: Challenge) {
+        self.challenge = challenge;
+    }
 }
 
 /// Maximum number of clients on a game server
@@ -127,11 +530,43 @@ pub struct InfoKey(Vec<u8>);
 
 impl InfoKey {
     /// Creates a new `InfoKey` from a container of bytes.
-    pub fn new<T: Into<Vec<u8>>>(t: T) -> Result<Self> {
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::InfoKey;
+    /// let key = InfoKey::new(*b"sv_maxclients")?;
+    /// # Ok::<(), dpmaster_proto::error::InvalidInfoKeyError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [InvalidByteError](crate::error::InvalidByteError)
+    /// if a supplied byte is the `\` key-value delimiter.
+    /// ```rust
+    /// # use dpmaster_proto::{error::InvalidInfoKeyError, messages::InfoKey};
+    /// #
+    /// assert!(matches!(InfoKey::new(*b"uh\\oh").unwrap_err(), InvalidInfoKeyError::InvalidByte(..)));
+    /// ```
+    pub fn new<T: Into<Vec<u8>>>(t: T) -> Result<Self, InvalidInfoKeyError> {
         let bytes = t.into();
 
+        if let Some(offset) = bytes.iter().position(|byte| *byte == b'\\') {
+            return Err(InvalidByteError(offset, bytes))?;
+        }
+
         Ok(Self(bytes))
     }
+
+    /// Returns the number of bytes in this `InfoKey`.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if this `InfoKey` contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl<I: std::slice::SliceIndex<[u8]>> std::ops::Index<I> for InfoKey {
@@ -142,16 +577,81 @@ impl<I: std::slice::SliceIndex<[u8]>> std::ops::Index<I> for InfoKey {
     }
 }
 
+/// Hashes and compares like the bytes, so an [`Info`](Info) can be looked up by them, see [`Info::get_bytes`]
+impl std::borrow::Borrow<[u8]> for InfoKey {
+    fn borrow(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 /// Value in a [`Info`](Info) key-value pair
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct InfoValue(Vec<u8>);
 
 impl InfoValue {
     /// Creates a new `InfoValue` from a container of bytes.
-    pub fn new<T: Into<Vec<u8>>>(t: T) -> Result<Self> {
-        let bytes = t.into();
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::InfoValue;
+    /// let value = InfoValue::new(*b"My Server\n")?;
+    /// # Ok::<(), dpmaster_proto::error::InvalidInfoValueError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [InvalidByteError](crate::error::InvalidByteError)
+    /// if a supplied byte is the `\` key-value delimiter.
+    /// ```rust
+    /// # use dpmaster_proto::{error::InvalidInfoValueError, messages::InfoValue};
+    /// #
+    /// assert!(matches!(InfoValue::new(*b"uh\\oh").unwrap_err(), InvalidInfoValueError::InvalidByte(..)));
+    /// ```
+    pub fn new<T: Into<Vec<u8>>>(t: T) -> Result<Self, InvalidInfoValueError> {
+        Self::validate(t.into(), b"\\")
+    }
 
-        Ok(Self(bytes))
+    /// Creates a new `InfoValue` from a container of bytes, rejecting control characters.
+    ///
+    /// Like [`new`](InfoValue::new), but additionally rejects the `\n`, `\r` and `\0` bytes
+    /// which break the line-based framing of an [`infoResponse` message](InfoResponseMessage) for some readers.
+    ///
+    /// # Errors
+    ///
+    /// Will return [InvalidByteError](crate::error::InvalidByteError)
+    /// if a supplied byte is the `\` key-value delimiter or one of `\n`, `\r` or `\0`.
+    /// ```rust
+    /// # use dpmaster_proto::{error::InvalidInfoValueError, messages::InfoValue};
+    /// #
+    /// assert!(matches!(InfoValue::new_strict(*b"My Server\n").unwrap_err(), InvalidInfoValueError::InvalidByte(..)));
+    /// ```
+    pub fn new_strict<T: Into<Vec<u8>>>(t: T) -> Result<Self, InvalidInfoValueError> {
+        Self::validate(t.into(), b"\\\n\r\0")
+    }
+
+    /// Compares this `InfoValue` with `other` in constant time, e.g. for a `challenge` value.
+    ///
+    /// See [`Challenge::ct_eq`].
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        ct_eq(&self.0, &other.0)
+    }
+
+    fn validate(bytes: Vec<u8>, invalid: &[u8]) -> Result<Self, InvalidInfoValueError> {
+        match bytes.iter().position(|byte| invalid.contains(byte)) {
+            Some(offset) => Err(InvalidByteError(offset, bytes))?,
+            None => Ok(Self(bytes)),
+        }
+    }
+
+    /// Returns the number of bytes in this `InfoValue`.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if this `InfoValue` contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 }
 
@@ -163,6 +663,44 @@ impl<I: std::slice::SliceIndex<[u8]>> std::ops::Index<I> for InfoValue {
     }
 }
 
+/// Implements comparisons of a bytes newtype against `[u8]`, `&[u8]` and `&str`, so literals need no wrapping
+macro_rules! impl_bytes_eq {
+    ($($t:ty),*) => {
+        $(
+            impl PartialEq<[u8]> for $t {
+                fn eq(&self, other: &[u8]) -> bool {
+                    self.0[..] == *other
+                }
+            }
+
+            impl PartialEq<&[u8]> for $t {
+                fn eq(&self, other: &&[u8]) -> bool {
+                    self.0[..] == **other
+                }
+            }
+
+            impl PartialEq<&str> for $t {
+                fn eq(&self, other: &&str) -> bool {
+                    self.0[..] == *other.as_bytes()
+                }
+            }
+        )*
+    };
+}
+
+impl_bytes_eq!(InfoKey, InfoValue);
+
+/// Keys an [`Info`](Info) of an [`infoResponse` message](InfoResponseMessage) has to contain
+///
+/// See [`Info::validate_required`].
+pub const REQUIRED_INFO_KEYS: [&str; 5] = [
+    "challenge",
+    "sv_maxclients",
+    "protocol",
+    "clients",
+    "gamename",
+];
+
 /// Map of [`InfoKey`](InfoKey)-[`InfoValue`](InfoValue) pairs
 ///
 /// Contained in an [`infoResponse` message](InfoResponseMessage).
@@ -176,60 +714,339 @@ impl Info {
         Self(indexmap::IndexMap::new())
     }
 
+    /// Creates a new, empty `Info` with space for at least `capacity` key-value pairs.
+    ///
+    /// The capacity is only a hint to avoid reallocations while inserting, the `Info` grows as needed.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(indexmap::IndexMap::with_capacity(capacity))
+    }
+
+    /// Returns the number of key-value pairs this `Info` can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
     pub fn insert(&mut self, key: InfoKey, value: InfoValue) {
         self.0.insert(key, value);
     }
 
+    /// Removes the `key` and returns its value, if it was contained.
+    ///
+    /// Preserves the order of the remaining key-value pairs.
+    pub fn remove(&mut self, key: &InfoKey) -> Option<InfoValue> {
+        self.0.shift_remove(key)
+    }
+
     pub fn iter(&self) -> indexmap::map::Iter<'_, InfoKey, InfoValue> {
         self.0.iter()
     }
 
-    pub fn challenge(&self) -> &Challenge {
-        todo!();
+    /// Returns the key-value pairs of this `Info` sorted by key, instead of in insertion order.
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::messages::{Info, InfoKey, InfoValue};
+    /// let mut info = Info::new();
+    /// info.insert(InfoKey::new(*b"sv_maxclients")?, InfoValue::new(*b"8")?);
+    /// info.insert(InfoKey::new(*b"clients")?, InfoValue::new(*b"0")?);
+    /// let keys: Vec<_> = info.sorted_by_key().into_iter().map(|(key, _)| &key[..]).collect();
+    /// assert_eq!(keys, [&b"clients"[..], b"sv_maxclients"]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn sorted_by_key(&self) -> Vec<(&InfoKey, &InfoValue)> {
+        let mut pairs: Vec<_> = self.0.iter().collect();
+        pairs.sort_unstable_by(|(a, _), (b, _)| a[..].cmp(&b[..]));
+        pairs
     }
 
-    pub fn sv_maxclients(&self) -> MaxClientsNumber {
-        todo!();
+    /// Returns the value of `key`, if it is contained.
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::messages::{Info, InfoKey, InfoValue};
+    /// let mut info = Info::new();
+    /// info.insert(InfoKey::new(*b"sv_maxclients")?, InfoValue::new(*b"8")?);
+    /// assert_eq!(info.get(&InfoKey::new(*b"sv_maxclients")?), Some(&InfoValue::new(*b"8")?));
+    /// assert_eq!(info.get(&InfoKey::new(*b"clients")?), None);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get(&self, key: &InfoKey) -> Option<&InfoValue> {
+        self.0.get(key)
     }
 
-    pub fn protocol(&self) -> ProtocolNumber {
-        todo!();
+    /// Like [`get`](Info::get), but without constructing an `InfoKey` from the `key` bytes
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::messages::{Info, InfoKey, InfoValue};
+    /// let mut info = Info::new();
+    /// info.insert(InfoKey::new(*b"sv_maxclients")?, InfoValue::new(*b"8")?);
+    /// assert_eq!(info.get_bytes(b"sv_maxclients"), Some(&InfoValue::new(*b"8")?));
+    /// assert_eq!(info.get_bytes(b"clients"), None);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get_bytes(&self, key: &[u8]) -> Option<&InfoValue> {
+        self.0.get(key)
     }
 
-    pub fn clients(&self) -> ClientsNumber {
-        todo!();
+    fn value(&self, key: &str) -> Option<&InfoValue> {
+        self.get_bytes(key.as_bytes())
     }
 
-    pub fn gamename(&self) -> Option<&GameName> {
-        todo!();
+    /// Parses the value of `key` as a decimal number, without the sign or whitespace `str::parse` would accept
+    fn number<T: std::str::FromStr>(
+        &self,
+        key: &'static str,
+    ) -> std::result::Result<T, InfoFieldError> {
+        let value = self.value(key).ok_or(InfoFieldError::Missing(key))?;
+        if value.is_empty() || !value[..].iter().all(u8::is_ascii_digit) {
+            return Err(InfoFieldError::Invalid(key));
+        }
+        std::str::from_utf8(&value[..])
+            .expect("ASCII digits are UTF-8")
+            .parse()
+            .map_err(|_| InfoFieldError::Invalid(key))
     }
 
-    pub fn gametype(&self) -> Option<&GameType> {
-        todo!();
+    /// Checks that this `Info` contains all of the [`REQUIRED_INFO_KEYS`].
+    ///
+    /// A master server can use this to reject incomplete server reports.
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::messages::{Info, InfoKey, InfoValue};
+    /// let mut info = Info::new();
+    /// info.insert(InfoKey::new(*b"challenge")?, InfoValue::new(*b"A_ch4Lleng3")?);
+    /// info.insert(InfoKey::new(*b"clients")?, InfoValue::new(*b"0")?);
+    /// assert_eq!(info.validate_required(), Err(vec!["sv_maxclients", "protocol", "gamename"]));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return the missing keys, in the order of [`REQUIRED_INFO_KEYS`].
+    pub fn validate_required(&self) -> std::result::Result<(), Vec<&'static str>> {
+        let missing: Vec<_> = REQUIRED_INFO_KEYS
+            .iter()
+            .copied()
+            .filter(|required| !self.0.keys().any(|key| &key[..] == required.as_bytes()))
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
+        }
     }
-}
-
-/// `infoResponse` message
-///
-/// Sent concludingly from a game server to the master server in response to a [`getinfo` message](GetInfoMessage) from the master server.
-///
-/// Contains [`Info`](Info) metadata.
-#[derive(Debug, PartialEq, Eq)]
-pub struct InfoResponseMessage {
-    info: Info,
-}
 
-impl InfoResponseMessage {
-    /// Creates a new `InfoResponseMessage` for the given `info`.
-    pub fn new(info: Info) -> Self {
-        Self { info }
+    /// Converts this `Info` into a map of UTF-8 strings, e.g. for templating or JSON.
+    ///
+    /// With `lossy`, invalid UTF-8 sequences are replaced with `U+FFFD`, otherwise they are an error.
+    /// Either way the order of the key-value pairs is lost.
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::messages::{Info, InfoKey, InfoValue};
+    /// let mut info = Info::new();
+    /// info.insert(InfoKey::new(*b"hostname")?, InfoValue::new(*b"caf\xE9")?);
+    /// assert!(info.to_string_map(false).is_err());
+    /// assert_eq!(info.to_string_map(true)?["hostname"], "caf\u{FFFD}");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error for the first key or value which is not valid UTF-8, unless `lossy`.
+    pub fn to_string_map(
+        &self,
+        lossy: bool,
+    ) -> std::result::Result<std::collections::HashMap<String, String>, std::string::FromUtf8Error>
+    {
+        let to_string = |bytes: &[u8]| {
+            if lossy {
+                Ok(String::from_utf8_lossy(bytes).into_owned())
+            } else {
+                String::from_utf8(bytes.to_vec())
+            }
+        };
+        self.0
+            .iter()
+            .map(|(key, value)| Ok((to_string(&key[..])?, to_string(&value[..])?)))
+            .collect()
     }
 
-    /// Returns the `Info` contained in this message.
-    pub fn info(&self) -> &Info {
-        &self.info
-    }
-}
+    /// Returns the `Challenge` echoed by the game server, if any.
+    ///
+    /// This correlates an `infoResponse` with the `getinfo` it answers.
+    /// To authenticate the game server, see [`ChallengeFlow`](crate::flow::ChallengeFlow) instead.
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::messages::{Challenge, Info, InfoKey, InfoValue};
+    /// let mut info = Info::new();
+    /// assert_eq!(info.challenge(), Ok(None));
+    ///
+    /// info.insert(InfoKey::new(*b"challenge")?, InfoValue::new(*b"A_ch4Lleng3")?);
+    /// assert_eq!(info.challenge(), Ok(Some(Challenge::new(*b"A_ch4Lleng3")?)));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [`InvalidChallengeError`] if the value of the `challenge` key is not a valid `Challenge`,
+    /// e.g. because it is empty.
+    pub fn challenge(&self) -> std::result::Result<Option<Challenge>, InvalidChallengeError> {
+        self.value("challenge")
+            .map(|value| Challenge::new(value[..].to_vec()))
+            .transpose()
+    }
+
+    /// Returns the maximum number of clients of the game server.
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::messages::{Info, InfoKey, InfoValue};
+    /// let mut info = Info::new();
+    /// info.insert(InfoKey::new(*b"sv_maxclients")?, InfoValue::new(*b"8")?);
+    /// assert_eq!(info.sv_maxclients()?.get(), 8);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [`InfoFieldError::Missing`] if there is no `sv_maxclients` key
+    /// and [`InfoFieldError::Invalid`] if its value is not a non-zero decimal number.
+    pub fn sv_maxclients(&self) -> std::result::Result<MaxClientsNumber, InfoFieldError> {
+        self.number("sv_maxclients")
+    }
+
+    /// Returns the protocol number of the game server.
+    ///
+    /// A game client can compare this with the protocol number it queried for.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`InfoFieldError::Missing`] if there is no `protocol` key
+    /// and [`InfoFieldError::Invalid`] if its value is not a non-zero decimal number fitting into a `u32`.
+    pub fn protocol(&self) -> std::result::Result<ProtocolNumber, InfoFieldError> {
+        self.number("protocol")
+    }
+
+    /// Returns the number of clients currently connected to the game server.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`InfoFieldError::Missing`] if there is no `clients` key
+    /// and [`InfoFieldError::Invalid`] if its value is not a decimal number fitting into a `u32`.
+    pub fn clients(&self) -> std::result::Result<ClientsNumber, InfoFieldError> {
+        self.number("clients")
+    }
+
+    /// Returns the `GameName` of the game server, if any.
+    ///
+    /// Game servers of games with their own master server often omit it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::messages::{GameName, Info, InfoKey, InfoValue};
+    /// let mut info = Info::new();
+    /// assert_eq!(info.gamename()?, None);
+    ///
+    /// info.insert(InfoKey::new(*b"gamename")?, InfoValue::new(*b"Xonotic")?);
+    /// assert_eq!(info.gamename()?, Some(GameName::new(*b"Xonotic")?));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [`ProtocolError::InvalidGameName`] if the value of the `gamename` key is not a valid `GameName`.
+    pub fn gamename(&self) -> Result<Option<GameName>> {
+        self.value("gamename")
+            .map(|value| GameName::new(value[..].to_vec()))
+            .transpose()
+    }
+
+    /// Returns the `GameType` of the game server, if any.
+    ///
+    /// Any value is a valid `GameType` for now, so this does not fail yet.
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::messages::{GameType, Info, InfoKey, InfoValue};
+    /// let mut info = Info::new();
+    /// assert_eq!(info.gametype()?, None);
+    ///
+    /// info.insert(InfoKey::new(*b"gametype")?, InfoValue::new(*b"ctf")?);
+    /// assert_eq!(info.gametype()?, Some(GameType::new(*b"ctf")?));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn gametype(&self) -> Result<Option<GameType>> {
+        self.value("gametype")
+            .map(|value| GameType::new(value[..].to_vec()))
+            .transpose()
+    }
+}
+
+/// Hashes the key-value pairs [sorted by key](Info::sorted_by_key), like `==` ignores their insertion order
+impl std::hash::Hash for Info {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.sorted_by_key().hash(state);
+    }
+}
+
+/// `infoResponse` message
+///
+/// Sent concludingly from a game server to the master server in response to a [`getinfo` message](GetInfoMessage) from the master server.
+///
+/// Contains [`Info`](Info) metadata.
+#[derive(Debug, PartialEq, Eq)]
+pub struct InfoResponseMessage {
+    info: Info,
+}
+
+impl InfoResponseMessage {
+    /// Creates a new `InfoResponseMessage` for the given `info`.
+    pub fn new(info: Info) -> Self {
+        Self { info }
+    }
+
+    /// Returns the `Info` contained in this message.
+    pub fn info(&self) -> &Info {
+        &self.info
+    }
+
+    /// Returns the mutable `Info` contained in this message.
+    ///
+    /// This allows to e.g. remove sensitive keys before forwarding the message.
+    pub fn info_mut(&mut self) -> &mut Info {
+        &mut self.info
+    }
+
+    /// Returns `true` if the `gamename` key of this message matches the game `name` a client filtered on.
+    ///
+    /// No filter, i.e. `None`, matches any game. Otherwise the `Info` has to contain a `gamename` key with the exact same value,
+    /// like for the [`game_name`](GetServersMessage::game_name) of a [`getservers` message](GetServersMessage).
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::messages::{GameName, Info, InfoKey, InfoResponseMessage, InfoValue};
+    /// let mut info = Info::new();
+    /// info.insert(InfoKey::new(*b"gamename")?, InfoValue::new(*b"Nexuiz")?);
+    /// let inforesponse = InfoResponseMessage::new(info);
+    /// assert!(inforesponse.gamename_matches(Some(&GameName::from_static(b"Nexuiz")?)));
+    /// assert!(!inforesponse.gamename_matches(Some(&GameName::from_static(b"Xonotic")?)));
+    /// assert!(inforesponse.gamename_matches(None));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn gamename_matches(&self, name: Option<&GameName>) -> bool {
+        match name {
+            None => true,
+            Some(name) => self
+                .info
+                .iter()
+                .any(|(key, value)| &key[..] == b"gamename" && value[..] == name[..]),
+        }
+    }
+}
 
 /// Protocol name
 ///
@@ -244,6 +1061,16 @@ impl ProtocolName {
         let bytes = t.into();
         Ok(Self(bytes))
     }
+
+    /// Returns the number of bytes in this `ProtocolName`.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if this `ProtocolName` contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl<I: std::slice::SliceIndex<[u8]>> std::ops::Index<I> for ProtocolName {
@@ -287,20 +1114,80 @@ impl HeartbeatMessage {
 ///
 /// Contained in a [`getservers` message](GetServersMessage), [`getserversExt`](GetServersExtMessage)\
 /// and in the [`Info`](Info) of an [`infoResponse` message](InfoResponseMessage).
+///
+/// Protocol numbers start at `1`, so `0` is invalid.
 // TODO vs ProtocolName, GameType
-pub type ProtocolNumber = u32;
+pub type ProtocolNumber = std::num::NonZeroU32;
+
+/// Inclusive range of [`ProtocolNumber`]s
+///
+/// Lets a master server serving multiple versions of a game accept several protocol numbers.
+/// `ProtocolNumber` is ordered, so this is merely a shorthand for `min <= protocol_number <= max`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::messages::{ProtocolNumber, ProtocolRange};
+/// let range = ProtocolRange::new(ProtocolNumber::new(67).unwrap(), ProtocolNumber::new(71).unwrap());
+/// assert!(range.contains(ProtocolNumber::new(68).unwrap()));
+/// assert!(!range.contains(ProtocolNumber::new(84).unwrap()));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ProtocolRange {
+    min: ProtocolNumber,
+    max: ProtocolNumber,
+}
+
+impl ProtocolRange {
+    /// Creates a new `ProtocolRange` from `min` to `max`, both inclusive.
+    ///
+    /// If `min` is greater than `max`, the range is empty.
+    pub fn new(min: ProtocolNumber, max: ProtocolNumber) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns the smallest `ProtocolNumber` in this range.
+    pub fn min(&self) -> ProtocolNumber {
+        self.min
+    }
+
+    /// Returns the largest `ProtocolNumber` in this range.
+    pub fn max(&self) -> ProtocolNumber {
+        self.max
+    }
+
+    /// Returns `true` if `protocol_number` is contained in this range.
+    pub fn contains(&self, protocol_number: ProtocolNumber) -> bool {
+        self.min <= protocol_number && protocol_number <= self.max
+    }
+
+    /// Returns `true` if this range contains no protocol numbers.
+    pub fn is_empty(&self) -> bool {
+        self.min > self.max
+    }
+}
+
+impl From<ProtocolNumber> for ProtocolRange {
+    /// A range containing only `protocol_number`
+    fn from(protocol_number: ProtocolNumber) -> Self {
+        Self::new(protocol_number, protocol_number)
+    }
+}
 
 /// Game name
 ///
 /// Contained in a [`getservers` message](GetServersMessage), [`getserversExt`](GetServersExtMessage)\
 /// and in the [`Info`](Info) of an [`infoResponse` message](InfoResponseMessage).
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub struct GameName(Vec<u8>);
+///
+/// Backed by a [`Cow`](std::borrow::Cow), so `'static` literals do not need to be copied, see [`from_static`](GameName::from_static).
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct GameName(std::borrow::Cow<'static, [u8]>);
 
 impl GameName {
     /// Creates a new `GameName` from a container of bytes.
     ///
-    /// Game names can contain neither null bytes nor whitespace.
+    /// Game names can contain neither null bytes, spaces, newlines nor backslashes,
+    /// which would break the fields of a message apart.
     ///
     /// # Examples
     /// ```
@@ -311,7 +1198,7 @@ impl GameName {
     ///
     /// # Errors
     /// This function will return an error if the supplied bytes contain a
-    /// null/`0` byte or whitespace/`' '`.
+    /// null/`0` byte, whitespace/`' '`, a newline/`'\n'` or `'\r'` or a backslash/`'\\'`.
     /// The [`ProtocolError::InvalidGameName`] error will include the invalid byte
     /// as well as the first offset it occurred at.
     /// ```
@@ -322,21 +1209,69 @@ impl GameName {
     // FIXME: Comparing private fields in a public doctest feels wrong. Maybe compare debug output instead?
     pub fn new<T: Into<Vec<u8>>>(t: T) -> Result<Self> {
         let bytes = t.into();
-        match memchr2(b'\0', b' ', &bytes) {
+        Self::validate(&bytes)?;
+        Ok(Self(bytes.into()))
+    }
+
+    /// Creates a new `GameName` from borrowed bytes.
+    ///
+    /// Like [`new`](GameName::new), but only copies the bytes once they are validated.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`new`](GameName::new).
+    pub fn new_borrowed(bytes: &[u8]) -> Result<Self> {
+        Self::validate(bytes)?;
+        Ok(Self(bytes.to_vec().into()))
+    }
+
+    /// Creates a new `GameName` from `'static` bytes without copying them.
+    ///
+    /// # Examples
+    /// ```
+    /// use dpmaster_proto::GameName;
+    /// let game_name = GameName::from_static(b"Nexuiz")?;
+    /// assert_eq!(game_name, GameName::new(b"Nexuiz".to_vec())?);
+    /// # Ok::<(), dpmaster_proto::ProtocolError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`new`](GameName::new).
+    pub fn from_static(bytes: &'static [u8]) -> Result<Self> {
+        Self::validate(bytes)?;
+        Ok(Self(bytes.into()))
+    }
+
+    pub(crate) fn validate(bytes: &[u8]) -> Result<()> {
+        match bytes
+            .iter()
+            .position(|byte| [b'\0', b' ', b'\n', b'\r', b'\\'].contains(byte))
+        {
             Some(i) => Err(ProtocolError::InvalidGameName {
                 offset: i,
                 byte: bytes[i],
             }),
-            None => Ok(Self(bytes)),
+            None => Ok(()),
         }
     }
+
+    /// Returns the number of bytes in this `GameName`.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if this `GameName` contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl<I: std::slice::SliceIndex<[u8]>> std::ops::Index<I> for GameName {
     type Output = I::Output;
 
     fn index(&self, index: I) -> &Self::Output {
-        std::ops::Index::index(&self.0, index)
+        std::ops::Index::index(&*self.0, index)
     }
 }
 
@@ -354,22 +1289,104 @@ impl std::str::FromStr for GameName {
 /// [`FilterExtOptions`](FilterExtOptions) of an [`getserversExt` message](GetServersExtMessage)\
 /// and [`Info`](Info) of an [`infoResponse` message](InfoResponseMessage).
 // TODO vs GameName, ProtocolNumber
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub struct GameType(Vec<u8>);
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct GameType(std::borrow::Cow<'static, [u8]>);
 
 impl GameType {
     /// Creates a new `GameType` from a container of bytes.
     pub fn new<T: Into<Vec<u8>>>(t: T) -> Result<Self> {
         let bytes = t.into();
-        Ok(Self(bytes))
+        Ok(Self(bytes.into()))
+    }
+
+    /// Creates a new `GameType` from borrowed bytes.
+    pub fn new_borrowed(bytes: &[u8]) -> Result<Self> {
+        Ok(Self(bytes.to_vec().into()))
+    }
+
+    /// Creates a new `GameType` from `'static` bytes without copying them.
+    pub fn from_static(bytes: &'static [u8]) -> Result<Self> {
+        Ok(Self(bytes.into()))
+    }
+
+    /// Returns the number of bytes in this `GameType`.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if this `GameType` contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if this `GameType` is numeric like `4`, i.e. consists of ASCII digits only.
+    ///
+    /// # Examples
+    /// ```
+    /// # use dpmaster_proto::GameType;
+    /// assert!(GameType::from_static(b"4")?.is_numeric());
+    /// assert!(!GameType::from_static(b"ctf")?.is_numeric());
+    /// # Ok::<(), dpmaster_proto::ProtocolError>(())
+    /// ```
+    pub fn is_numeric(&self) -> bool {
+        !self.0.is_empty() && self.0.iter().all(u8::is_ascii_digit)
+    }
+
+    /// Returns `true` if this `GameType` is textual like `ctf`, i.e. not [numeric](GameType::is_numeric) and not empty.
+    ///
+    /// Mixed game types like `4a` are textual as well.
+    pub fn is_textual(&self) -> bool {
+        !self.0.is_empty() && !self.is_numeric()
+    }
+
+    /// Returns the `GameType` for a bare gametype `keyword` of the game with the given `game_name`, if known.
+    ///
+    /// Without a `game_name` the game is assumed to be `Quake3Arena`, like dpmaster does.
+    ///
+    /// Some games accept e.g. `ffa` in the [`FilterOptions`](FilterOptions) of a [`getservers` message](GetServersMessage)
+    /// instead of the canonical `gametype=0`. The known keywords per game are listed in a table,
+    /// so `empty` and `full` are never gametype keywords.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::{GameName, GameType};
+    /// let game_name = GameName::new(*b"Quake3Arena")?;
+    /// assert_eq!(GameType::from_keyword(Some(&game_name), b"ffa"), Some(GameType::new(*b"0")?));
+    /// assert_eq!(GameType::from_keyword(None, b"ctf"), Some(GameType::new(*b"4")?));
+    /// assert_eq!(GameType::from_keyword(Some(&game_name), b"empty"), None);
+    /// # Ok::<(), dpmaster_proto::error::ProtocolError>(())
+    /// ```
+    pub fn from_keyword(game_name: Option<&GameName>, keyword: &[u8]) -> Option<Self> {
+        let game_name = game_name.map_or(&b"Quake3Arena"[..], |game_name| &game_name[..]);
+        GAMETYPE_KEYWORDS
+            .iter()
+            .find(|(name, _)| *name == game_name)
+            .and_then(|(_, keywords)| keywords.iter().find(|(k, _)| *k == keyword))
+            .map(|(_, gametype)| Self((*gametype).into()))
     }
 }
 
+/// Gametype keywords of a game and the `GameType` values they stand for
+type GameTypeKeywords = &'static [(&'static [u8], &'static [u8])];
+
+/// Gametype keywords per game name
+// TODO more games
+const GAMETYPE_KEYWORDS: &[(&[u8], GameTypeKeywords)] = &[(
+    b"Quake3Arena",
+    &[
+        (b"ffa", b"0"),
+        (b"tourney", b"1"),
+        (b"team", b"3"),
+        (b"ctf", b"4"),
+    ],
+)];
+
 impl<I: std::slice::SliceIndex<[u8]>> std::ops::Index<I> for GameType {
     type Output = I::Output;
 
     fn index(&self, index: I) -> &Self::Output {
-        std::ops::Index::index(&self.0, index)
+        std::ops::Index::index(&*self.0, index)
     }
 }
 
@@ -386,7 +1403,7 @@ impl std::str::FromStr for GameType {
 /// Contains a [`GameType`](GameType) and "empty" / "full" options.
 ///
 /// IPv4-only variant of [`FilterExtOptions`](FilterExtOptions).
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct FilterOptions {
     /// `gametype=X` filter option
     gametype: Option<GameType>,
@@ -420,6 +1437,21 @@ impl FilterOptions {
     pub fn full(&self) -> bool {
         self.full
     }
+
+    /// Sets the `GameType` option of this filter.
+    pub fn set_gametype(&mut self, gametype: Option<GameType>) {
+        self.gametype = gametype;
+    }
+
+    /// Sets the "empty" option of this filter.
+    pub fn set_empty(&mut self, empty: bool) {
+        self.empty = empty;
+    }
+
+    /// Sets the "full" option of this filter.
+    pub fn set_full(&mut self, full: bool) {
+        self.full = full;
+    }
 }
 
 /// `getservers` message
@@ -430,7 +1462,7 @@ impl FilterOptions {
 /// Contains a [`GameName`](GameName), [`ProtocolNumber`](ProtocolNumber) and [`FilterOptions`](FilterOptions).
 ///
 /// IPv4-only variant of the [`getserversExt` message](GetServersExtMessage).
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct GetServersMessage {
     game_name: Option<GameName>,
     protocol_number: ProtocolNumber,
@@ -451,6 +1483,26 @@ impl GetServersMessage {
         }
     }
 
+    /// Creates a new `GetServersMessage` for the given `protocol_number`, without a game name and filter options.
+    ///
+    /// This is the most common minimal query, e.g. for the default game of a master server.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::{GetServersMessage, ProtocolNumber};
+    /// let message = GetServersMessage::for_protocol(ProtocolNumber::new(68).unwrap());
+    /// assert_eq!(message.game_name(), None);
+    /// assert!(!message.filter_options().empty());
+    /// ```
+    pub fn for_protocol(protocol_number: ProtocolNumber) -> Self {
+        Self::new(
+            None,
+            protocol_number,
+            FilterOptions::new(None, false, false),
+        )
+    }
+
     /// Returns the `GameName` contained in this message.
     pub fn game_name(&self) -> Option<&GameName> {
         self.game_name.as_ref()
@@ -465,6 +1517,21 @@ impl GetServersMessage {
     pub fn filter_options(&self) -> &FilterOptions {
         &self.filter_options
     }
+
+    /// Returns the mutable `FilterOptions` contained in this message.
+    pub fn filter_options_mut(&mut self) -> &mut FilterOptions {
+        &mut self.filter_options
+    }
+
+    /// Sets the `GameName` contained in this message.
+    pub fn set_game_name(&mut self, game_name: Option<GameName>) {
+        self.game_name = game_name;
+    }
+
+    /// Sets the `ProtocolNumber` contained in this message.
+    pub fn set_protocol_number(&mut self, protocol_number: ProtocolNumber) {
+        self.protocol_number = protocol_number;
+    }
 }
 
 /// `getserversResponse` message
@@ -474,16 +1541,24 @@ impl GetServersMessage {
 /// Contains a list of [`SocketAddrV4`](std::net::SocketAddrV4) and End-of-Transmission flag.
 ///
 /// IPv4-only variant of the [`getserversExtResponse` message](GetServersExtResponseMessage).
+///
+/// Some implementations, e.g. Xonotic with `d0_blind_id`, append data such as signatures after the EOT marker.
+/// These bytes are kept as an opaque [`trailer`](GetServersResponseMessage::trailer), verifying them is out of scope.
 #[derive(Debug, PartialEq, Eq)]
 pub struct GetServersResponseMessage {
     servers: Vec<std::net::SocketAddrV4>,
     eot: bool,
+    trailer: Vec<u8>,
 }
 
 impl GetServersResponseMessage {
     /// Creates a new `GetServersResponseMessage` for the given `servers` and "eot" flag.
     pub fn new(servers: Vec<std::net::SocketAddrV4>, eot: bool) -> Self {
-        Self { servers, eot }
+        Self {
+            servers,
+            eot,
+            trailer: Vec::new(),
+        }
     }
 
     /// Returns the server socket addresses contained in this message.
@@ -491,59 +1566,411 @@ impl GetServersResponseMessage {
         &self.servers[..]
     }
 
+    /// Consumes this message and returns the server socket addresses contained in it, without copying them.
+    pub fn into_servers(self) -> Vec<std::net::SocketAddrV4> {
+        self.servers
+    }
+
+    /// Returns a summary of the server socket addresses grouped by `/24` network.
+    ///
+    /// Each entry consists of the network address, prefix length and number of distinct server socket addresses,
+    /// ordered by network address. This is e.g. useful to log a large response concisely.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::GetServersResponseMessage;
+    /// # use std::net::Ipv4Addr;
+    /// let message = GetServersResponseMessage::new(
+    ///     vec!["192.0.2.1:27960".parse()?, "192.0.2.2:27960".parse()?, "198.51.100.1:27960".parse()?],
+    ///     true,
+    /// );
+    /// assert_eq!(
+    ///     message.subnets(),
+    ///     vec![(Ipv4Addr::new(192, 0, 2, 0), 24, 2), (Ipv4Addr::new(198, 51, 100, 0), 24, 1)]
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn subnets(&self) -> Vec<(std::net::Ipv4Addr, u8, usize)> {
+        subnets(self.servers.iter().map(|server| {
+            let [a, b, c, _] = server.ip().octets();
+            (std::net::Ipv4Addr::new(a, b, c, 0), 24, server)
+        }))
+    }
+
     /// Returns the EOT flag contained in this message.
     pub fn eot(&self) -> bool {
         self.eot
     }
-}
 
-/// Filter options for a [`getserversExt` message](GetServersExtMessage)
-///
-/// Contains a [`GameType`](GameType), "empty" / "full" and "ipv4" / "ipv6" options.
-///
-/// IPv6-enabled variant of [`FilterOptions`](FilterOptions).
-pub struct FilterExtOptions {
-    /// `gametype=X` filter option
-    gametype: Option<GameType>,
-    /// empty servers option
-    empty: bool,
-    /// full servers option
-    full: bool,
-    // IPv4 servers option
-    ipv4: bool,
-    // IPv6 servers option
-    ipv6: bool,
-}
+    /// Lazily splits `servers` into messages which each serialize to at most `max_len` bytes.
+    ///
+    /// Only the last message has the EOT flag set. An empty list of `servers` results in a single, empty message.\
+    /// Each message contains at least one server, so check [`serialized_len`](GetServersResponseMessage::serialized_len)
+    /// if `max_len` might be too small for even that.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::GetServersResponseMessage;
+    /// let servers = vec!["192.0.2.1:27960".parse()?; 300];
+    /// let messages: Vec<_> = GetServersResponseMessage::paginate(&servers, 1400).collect();
+    /// assert_eq!(messages.len(), 2);
+    /// assert!(messages.iter().all(|message| message.serialized_len() <= 1400));
+    /// assert!(messages[1].eot());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn paginate(
+        servers: &[std::net::SocketAddrV4],
+        max_len: usize,
+    ) -> impl Iterator<Item = Self> + '_ {
+        let empty = Self::new(Vec::new(), true);
+        let per_message = (max_len.saturating_sub(empty.serialized_len()) / IPV4_ENTRY_LEN).max(1);
+        Pages {
+            servers: Some(servers),
+            per_message,
+        }
+    }
 
-impl FilterExtOptions {
-    /// Creates a new `FilterExtOptions` for the given `gametype`, `empty` / `full` and `ìpv4` / `ipv6` options.
-    pub fn new(
-        gametype: Option<GameType>,
-        empty: bool,
-        full: bool,
-        ipv4: bool,
-        ipv6: bool,
-    ) -> Self {
-        Self {
-            gametype,
-            empty,
-            full,
-            ipv4,
-            ipv6,
+    /// Returns the number of bytes of this message once serialized.
+    ///
+    /// This allows to e.g. check whether the message fits into a single datagram before serializing it.
+    pub fn serialized_len(&self) -> usize {
+        let mut len = MESSAGE_PREFIX_LEN
+            + Command::GetServersResponse.as_bytes().len()
+            + self.servers.len() * IPV4_ENTRY_LEN;
+        if self.eot {
+            len += EOT_LEN + self.trailer.len();
         }
+        len
     }
 
-    /// Returns the `GameType` option contained in this filter.
-    pub fn gametype(&self) -> Option<&GameType> {
-        self.gametype.as_ref()
+    /// Appends the server socket address `server` to this message.
+    pub fn push(&mut self, server: impl Into<std::net::SocketAddrV4>) {
+        self.servers.push(server.into());
     }
 
-    /// Returns the "empty" option contained in this filter.
-    pub fn empty(&self) -> bool {
-        self.empty
+    /// Returns this message with the server socket address `server` appended.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::GetServersResponseMessage;
+    /// # use std::net::{Ipv4Addr, SocketAddrV4};
+    /// let message = GetServersResponseMessage::new(vec![], true)
+    ///     .with_server(SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960))
+    ///     .with_server(SocketAddrV4::new(Ipv4Addr::new(198, 51, 100, 2), 27961));
+    /// assert_eq!(message.servers().len(), 2);
+    /// ```
+    pub fn with_server(mut self, server: impl Into<std::net::SocketAddrV4>) -> Self {
+        self.push(server);
+        self
+    }
+
+    /// Retains only the server socket addresses for which `f` returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::GetServersResponseMessage;
+    /// let mut message = GetServersResponseMessage::new(vec!["192.0.2.1:27960".parse()?, "10.0.0.1:27960".parse()?], true);
+    /// message.retain(|server| !server.ip().is_private());
+    /// assert_eq!(message.servers(), &["192.0.2.1:27960".parse()?]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn retain(&mut self, f: impl FnMut(&std::net::SocketAddrV4) -> bool) {
+        self.servers.retain(f);
+    }
+
+    /// Appends the server socket addresses of `other`, and adopts its EOT flag and trailer.
+    ///
+    /// This is e.g. for a client reassembling the results of a query from several `getserversResponse` messages,
+    /// where only the last one carries the EOT marker.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use dpmaster_proto::messages::GetServersResponseMessage;
+    /// let mut message = GetServersResponseMessage::new(vec!["192.0.2.1:27960".parse()?], false);
+    /// message.extend_from(GetServersResponseMessage::new(vec!["198.51.100.2:27961".parse()?], true));
+    /// assert_eq!(message.servers().len(), 2);
+    /// assert!(message.eot());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn extend_from(&mut self, other: GetServersResponseMessage) {
+        self.servers.extend(other.servers);
+        self.eot = other.eot;
+        self.trailer = other.trailer;
+    }
+
+    /// Sets the EOT flag of this message.
+    pub fn set_eot(&mut self, eot: bool) {
+        self.eot = eot;
+    }
+
+    /// Returns the opaque bytes following the EOT marker in this message.
+    pub fn trailer(&self) -> &[u8] {
+        &self.trailer[..]
+    }
+
+    /// Sets the opaque bytes following the EOT marker in this message.
+    ///
+    /// The trailer is only serialized if the EOT flag is set.
+    pub fn set_trailer(&mut self, trailer: Vec<u8>) {
+        self.trailer = trailer;
+    }
+}
+
+/// Compares only the server socket addresses, i.e. ignores the EOT flag and trailer.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::messages::GetServersResponseMessage;
+/// # use std::net::SocketAddrV4;
+/// let server: SocketAddrV4 = "192.0.2.1:27960".parse()?;
+/// let message = GetServersResponseMessage::new(vec![server], true);
+/// assert_eq!(message, [server][..]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+impl PartialEq<[std::net::SocketAddrV4]> for GetServersResponseMessage {
+    fn eq(&self, other: &[std::net::SocketAddrV4]) -> bool {
+        self.servers() == other
+    }
+}
+
+/// Compares only the server socket addresses, i.e. ignores the EOT flag and trailer.
+impl PartialEq<Vec<std::net::SocketAddrV4>> for GetServersResponseMessage {
+    fn eq(&self, other: &Vec<std::net::SocketAddrV4>) -> bool {
+        self.servers() == &other[..]
+    }
+}
+
+/// Iterator returned by [`GetServersResponseMessage::paginate`]
+struct Pages<'a> {
+    /// Servers not yet yielded, or `None` once the final message was yielded
+    servers: Option<&'a [std::net::SocketAddrV4]>,
+    per_message: usize,
+}
+
+impl Iterator for Pages<'_> {
+    type Item = GetServersResponseMessage;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let servers = self.servers?;
+        let (page, rest) = servers.split_at(servers.len().min(self.per_message));
+        let eot = rest.is_empty();
+        self.servers = if eot { None } else { Some(rest) };
+        Some(GetServersResponseMessage::new(page.to_vec(), eot))
+    }
+}
+
+/// Collects server socket addresses into a single, final message, i.e. with the EOT flag set.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::messages::GetServersResponseMessage;
+/// # use std::net::{Ipv4Addr, SocketAddrV4};
+/// let message: GetServersResponseMessage = (1..=3)
+///     .map(|i| SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, i), 27960))
+///     .collect();
+/// assert_eq!(message.servers().len(), 3);
+/// assert!(message.eot());
+/// ```
+impl std::iter::FromIterator<std::net::SocketAddrV4> for GetServersResponseMessage {
+    fn from_iter<T: IntoIterator<Item = std::net::SocketAddrV4>>(iter: T) -> Self {
+        Self::new(iter.into_iter().collect(), true)
+    }
+}
+
+impl AsRef<[std::net::SocketAddrV4]> for GetServersResponseMessage {
+    fn as_ref(&self) -> &[std::net::SocketAddrV4] {
+        self.servers()
+    }
+}
+
+/// Compares the servers of two `getserversResponse` messages
+///
+/// Returns the servers that are in `new` but not in `old` ("came online")
+/// and those that are in `old` but not in `new` ("went offline"), each in the order of their message.
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::messages::{diff_servers, GetServersResponseMessage};
+/// let old = GetServersResponseMessage::new(vec!["192.0.2.1:27960".parse()?, "192.0.2.2:27960".parse()?], true);
+/// let new = GetServersResponseMessage::new(vec!["192.0.2.2:27960".parse()?, "192.0.2.3:27960".parse()?], true);
+/// let (added, removed) = diff_servers(&old, &new);
+/// assert_eq!(added, vec!["192.0.2.3:27960".parse()?]);
+/// assert_eq!(removed, vec!["192.0.2.1:27960".parse()?]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn diff_servers(
+    old: &GetServersResponseMessage,
+    new: &GetServersResponseMessage,
+) -> (Vec<std::net::SocketAddrV4>, Vec<std::net::SocketAddrV4>) {
+    use std::collections::HashSet;
+
+    let old_servers: HashSet<_> = old.servers().iter().collect();
+    let new_servers: HashSet<_> = new.servers().iter().collect();
+    let added = new
+        .servers()
+        .iter()
+        .filter(|server| !old_servers.contains(server))
+        .copied()
+        .collect();
+    let removed = old
+        .servers()
+        .iter()
+        .filter(|server| !new_servers.contains(server))
+        .copied()
+        .collect();
+    (added, removed)
+}
+
+/// Builder for [`getserversResponse` messages](GetServersResponseMessage)
+///
+/// A master server may respond to a [`getservers` message](GetServersMessage) with multiple `getserversResponse` messages,
+/// but only the final one must have the End-of-Transmission flag set.\
+/// The builder thus does not allow to set the flag directly, instead it is set by [`build_final`](GetServersResponseMessageBuilder::build_final)
+/// and never set by [`build`](GetServersResponseMessageBuilder::build).
+///
+/// # Examples
+///
+/// ```rust
+/// # use dpmaster_proto::messages::GetServersResponseMessageBuilder;
+/// let first = GetServersResponseMessageBuilder::new()
+///     .server("192.0.2.1:27960".parse()?)
+///     .build()?;
+/// assert!(!first.eot());
+///
+/// let last = GetServersResponseMessageBuilder::new()
+///     .server("198.51.100.2:27961".parse()?)
+///     .build_final()?;
+/// assert!(last.eot());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct GetServersResponseMessageBuilder {
+    servers: Vec<std::net::SocketAddrV4>,
+    allow_empty: bool,
+}
+
+impl GetServersResponseMessageBuilder {
+    /// Creates a new `GetServersResponseMessageBuilder` without any servers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the server socket address `server`.
+    pub fn server(mut self, server: std::net::SocketAddrV4) -> Self {
+        self.servers.push(server);
+        self
+    }
+
+    /// Adds all the server socket addresses in `servers`.
+    pub fn servers<I: IntoIterator<Item = std::net::SocketAddrV4>>(mut self, servers: I) -> Self {
+        self.servers.extend(servers);
+        self
+    }
+
+    /// Allows to build a message without any servers.
+    ///
+    /// This is e.g. needed to respond to a query that did not match any game servers.
+    pub fn allow_empty(mut self) -> Self {
+        self.allow_empty = true;
+        self
+    }
+
+    /// Builds a non-final `GetServersResponseMessage`, i.e. without the EOT flag.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [EmptyError](crate::error::EmptyError) if there are no servers
+    /// and [`allow_empty`](GetServersResponseMessageBuilder::allow_empty) was not called.
+    pub fn build(self) -> Result<GetServersResponseMessage, EmptyError> {
+        self.build_with_eot(false)
+    }
+
+    /// Builds the final `GetServersResponseMessage`, i.e. with the EOT flag.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [EmptyError](crate::error::EmptyError) if there are no servers
+    /// and [`allow_empty`](GetServersResponseMessageBuilder::allow_empty) was not called.
+    pub fn build_final(self) -> Result<GetServersResponseMessage, EmptyError> {
+        self.build_with_eot(true)
+    }
+
+    fn build_with_eot(self, eot: bool) -> Result<GetServersResponseMessage, EmptyError> {
+        if self.servers.is_empty() && !self.allow_empty {
+            return Err(EmptyError(()));
+        }
+
+        Ok(GetServersResponseMessage::new(self.servers, eot))
+    }
+}
+
+fn subnets<N: Ord, S: Ord>(servers: impl Iterator<Item = (N, u8, S)>) -> Vec<(N, u8, usize)> {
+    let mut subnets = std::collections::BTreeMap::<(N, u8), std::collections::BTreeSet<S>>::new();
+    for (network, prefix, server) in servers {
+        subnets.entry((network, prefix)).or_default().insert(server);
+    }
+    subnets
+        .into_iter()
+        .map(|((network, prefix), servers)| (network, prefix, servers.len()))
+        .collect()
+}
+
+/// Filter options for a [`getserversExt` message](GetServersExtMessage)
+///
+/// Contains a [`GameType`](GameType), "empty" / "full" and "ipv4" / "ipv6" options.
+///
+/// IPv6-enabled variant of [`FilterOptions`](FilterOptions).
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct FilterExtOptions {
+    /// `gametype=X` filter option
+    gametype: Option<GameType>,
+    /// empty servers option
+    empty: bool,
+    /// full servers option
+    full: bool,
+    /// IPv4 servers option
+    ipv4: bool,
+    /// IPv6 servers option
+    ipv6: bool,
+}
+
+impl FilterExtOptions {
+    /// Creates a new `FilterExtOptions` for the given `gametype`, `empty` / `full` and `ipv4` / `ipv6` options.
+    pub fn new(
+        gametype: Option<GameType>,
+        empty: bool,
+        full: bool,
+        ipv4: bool,
+        ipv6: bool,
+    ) -> Self {
+        Self {
+            gametype,
+            empty,
+            full,
+            ipv4,
+            ipv6,
+        }
+    }
+
+    /// Returns the `GameType` option contained in this filter.
+    pub fn gametype(&self) -> Option<&GameType> {
+        self.gametype.as_ref()
     }
 
     /// Returns the "empty" option contained in this filter.
+    pub fn empty(&self) -> bool {
+        self.empty
+    }
+
+    /// Returns the "full" option contained in this filter.
     pub fn full(&self) -> bool {
         self.full
     }
@@ -557,6 +1984,16 @@ impl FilterExtOptions {
     pub fn ipv6(&self) -> bool {
         self.ipv6
     }
+
+    /// Returns `true` if the address family of `server` was requested by this filter.
+    ///
+    /// Like dpmaster, requesting neither "ipv4" nor "ipv6" requests both.
+    pub fn accepts_family(&self, server: &std::net::SocketAddr) -> bool {
+        match server {
+            std::net::SocketAddr::V4(_) => self.ipv4 || !self.ipv6,
+            std::net::SocketAddr::V6(_) => self.ipv6 || !self.ipv4,
+        }
+    }
 }
 
 /// `getserversExt` message
@@ -567,6 +2004,7 @@ impl FilterExtOptions {
 /// Contains a [`GameName`](GameName), [`ProtocolNumber`](ProtocolNumber) and [`FilterExtOptions`](FilterExtOptions).
 ///
 /// IPv6-enabled variant of the [`getservers` message](GetServersMessage).
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct GetServersExtMessage {
     game_name: GameName,
     protocol_number: ProtocolNumber,
@@ -574,7 +2012,7 @@ pub struct GetServersExtMessage {
 }
 
 impl GetServersExtMessage {
-    /// Creates a new `GetServersMessage` for the given `game_name`, `protocol_number` and `filter_options`.
+    /// Creates a new `GetServersExtMessage` for the given `game_name`, `protocol_number` and `filter_options`.
     pub fn new(
         game_name: GameName,
         protocol_number: ProtocolNumber,
@@ -610,6 +2048,7 @@ impl GetServersExtMessage {
 /// Contains a list of [`SocketAddr`](std::net::SocketAddr) and End-of-Transmission flag.
 ///
 /// IPv6-enabled variant of the [`getserversResponse` message](GetServersResponseMessage).
+#[derive(Debug, PartialEq, Eq)]
 pub struct GetServersExtResponseMessage {
     servers: Vec<std::net::SocketAddr>,
     eot: bool,
@@ -626,8 +2065,1312 @@ impl GetServersExtResponseMessage {
         &self.servers
     }
 
+    /// Consumes this message and returns the server socket addresses contained in it, without copying them.
+    pub fn into_servers(self) -> Vec<std::net::SocketAddr> {
+        self.servers
+    }
+
+    /// Returns a summary of the server socket addresses grouped by `/24` IPv4 and `/64` IPv6 network.
+    ///
+    /// Like [`GetServersResponseMessage::subnets`], but for both address families.
+    pub fn subnets(&self) -> Vec<(std::net::IpAddr, u8, usize)> {
+        subnets(self.servers.iter().map(|server| match server.ip() {
+            std::net::IpAddr::V4(ip) => {
+                let [a, b, c, _] = ip.octets();
+                (std::net::Ipv4Addr::new(a, b, c, 0).into(), 24, server)
+            }
+            std::net::IpAddr::V6(ip) => {
+                let [a, b, c, d, ..] = ip.segments();
+                (
+                    std::net::Ipv6Addr::new(a, b, c, d, 0, 0, 0, 0).into(),
+                    64,
+                    server,
+                )
+            }
+        }))
+    }
+
+    /// Returns the number of IPv4 server socket addresses contained in this message.
+    pub fn ipv4_count(&self) -> usize {
+        self.servers
+            .iter()
+            .filter(|server| server.is_ipv4())
+            .count()
+    }
+
+    /// Returns the number of IPv6 server socket addresses contained in this message.
+    pub fn ipv6_count(&self) -> usize {
+        self.servers
+            .iter()
+            .filter(|server| server.is_ipv6())
+            .count()
+    }
+
+    /// Retains only the server socket addresses for which `f` returns `true`.
+    pub fn retain(&mut self, f: impl FnMut(&std::net::SocketAddr) -> bool) {
+        self.servers.retain(f);
+    }
+
     /// Returns the EOT flag contained in this message.
     pub fn eot(&self) -> bool {
         self.eot
     }
 }
+
+/// Collects server socket addresses into a single, final message, i.e. with the EOT flag set.
+impl std::iter::FromIterator<std::net::SocketAddr> for GetServersExtResponseMessage {
+    fn from_iter<T: IntoIterator<Item = std::net::SocketAddr>>(iter: T) -> Self {
+        Self::new(iter.into_iter().collect(), true)
+    }
+}
+
+impl AsRef<[std::net::SocketAddr]> for GetServersExtResponseMessage {
+    fn as_ref(&self) -> &[std::net::SocketAddr] {
+        self.servers()
+    }
+}
+
+/// `print` message
+///
+/// Sent from the master server instead of the expected response, e.g. with a human-readable error.\
+/// Not part of the dpmaster protocol itself, but common to the connectionless messages of Quake III Arena based games.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PrintMessage {
+    text: Vec<u8>,
+}
+
+impl PrintMessage {
+    /// Creates a new `PrintMessage` with the given `text`.
+    pub fn new(text: Vec<u8>) -> Self {
+        Self { text }
+    }
+
+    /// Returns the text of this message, which is not necessarily valid UTF-8.
+    pub fn text(&self) -> &[u8] {
+        &self.text[..]
+    }
+}
+
+/// Player on a game server
+///
+/// Contained in a [`statusResponse` message](StatusResponseMessage).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Player {
+    score: i32,
+    ping: u32,
+    name: Vec<u8>,
+}
+
+impl Player {
+    /// Creates a new `Player` with the given `score`, `ping` and `name`.
+    pub fn new(score: i32, ping: u32, name: Vec<u8>) -> Self {
+        Self { score, ping, name }
+    }
+
+    /// Returns the score of this player.
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    /// Returns the ping of this player in milliseconds.
+    pub fn ping(&self) -> u32 {
+        self.ping
+    }
+
+    /// Returns the name of this player, which can contain color codes.
+    pub fn name(&self) -> &[u8] {
+        &self.name[..]
+    }
+
+    /// Returns whether this player is a bot, i.e. has a ping of `0`.
+    pub fn is_bot(&self) -> bool {
+        self.ping == 0
+    }
+}
+
+/// `statusResponse` message
+///
+/// Sent from a game server to a game client in response to a `getstatus` message from the game client.\
+/// Not part of the dpmaster protocol itself, but game clients commonly use it to query the game servers listed by a master server.
+///
+/// Contains [`Info`](Info) metadata and a list of [`Player`](Player)s.
+#[derive(Debug, PartialEq, Eq)]
+pub struct StatusResponseMessage {
+    info: Info,
+    players: Vec<Player>,
+}
+
+impl StatusResponseMessage {
+    /// Creates a new `StatusResponseMessage` for the given `info` and `players`.
+    pub fn new(info: Info, players: Vec<Player>) -> Self {
+        Self { info, players }
+    }
+
+    /// Returns the `Info` contained in this message.
+    pub fn info(&self) -> &Info {
+        &self.info
+    }
+
+    /// Returns the players contained in this message.
+    pub fn players(&self) -> &[Player] {
+        &self.players[..]
+    }
+
+    /// Returns the number of players, including bots.
+    pub fn player_count(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Returns the number of bots, i.e. players with a ping of `0`.
+    pub fn bot_count(&self) -> usize {
+        self.players.iter().filter(|player| player.is_bot()).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, SocketAddrV4};
+
+    #[test]
+    fn test_len() {
+        assert_eq!(Challenge::new(*b"A_ch4Lleng3").unwrap().len(), 11);
+        assert_eq!(InfoKey::new(*b"sv_maxclients").unwrap().len(), 13);
+        assert_eq!(InfoValue::new(*b"8").unwrap().len(), 1);
+        assert_eq!(ProtocolName::default().len(), 10);
+        assert_eq!(GameName::new(*b"Nexuiz").unwrap().len(), 6);
+        assert_eq!(GameType::new(*b"ctf").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_is_empty() {
+        assert!(!Challenge::new(*b"A_ch4Lleng3").unwrap().is_empty());
+        assert!(InfoKey::new(*b"").unwrap().is_empty());
+        assert!(InfoValue::new(*b"").unwrap().is_empty());
+        assert!(!InfoValue::new(*b"8").unwrap().is_empty());
+        assert!(ProtocolName::new(*b"").unwrap().is_empty());
+        assert!(GameName::new(*b"").unwrap().is_empty());
+        assert!(GameType::new(*b"").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_getservers_hash() {
+        let query = || {
+            GetServersMessage::new(
+                Some(GameName::new(*b"Nexuiz").unwrap()),
+                ProtocolNumber::new(3).unwrap(),
+                FilterOptions::new(Some(GameType::new(*b"ctf").unwrap()), true, false),
+            )
+        };
+
+        let mut cache = std::collections::HashMap::new();
+        cache.insert(query(), "cached response");
+        assert_eq!(cache.get(&query()), Some(&"cached response"));
+
+        let mut other = query();
+        other.filter_options_mut().set_full(true);
+        assert_eq!(cache.get(&other), None);
+    }
+
+    #[test]
+    fn test_game_name_constructors() {
+        let game_name = GameName::new(b"Nexuiz".to_vec()).unwrap();
+        assert_eq!(GameName::new_borrowed(b"Nexuiz"), Ok(game_name.clone()));
+        assert_eq!(GameName::from_static(b"Nexuiz"), Ok(game_name));
+
+        let err = || ProtocolError::InvalidGameName {
+            byte: b' ',
+            offset: 7,
+        };
+        assert_eq!(GameName::new_borrowed(b"invalid example"), Err(err()));
+        assert_eq!(GameName::from_static(b"invalid example"), Err(err()));
+        assert!(GameName::from_static(b"uh\0oh").is_err());
+    }
+
+    #[test]
+    fn test_game_name_delimiters() {
+        for byte in [b'\n', b'\r', b'\\'] {
+            let bytes = [b'N', b'e', b'x', byte, b'u', b'i', b'z'];
+            assert_eq!(
+                GameName::new(bytes.to_vec()),
+                Err(ProtocolError::InvalidGameName { byte, offset: 3 })
+            );
+        }
+    }
+
+    #[test]
+    fn test_game_type_classification() {
+        let game_type = GameType::from_static(b"4").unwrap();
+        assert!(game_type.is_numeric());
+        assert!(!game_type.is_textual());
+
+        let game_type = GameType::from_static(b"ctf").unwrap();
+        assert!(!game_type.is_numeric());
+        assert!(game_type.is_textual());
+
+        let game_type = GameType::from_static(b"4a").unwrap();
+        assert!(!game_type.is_numeric());
+        assert!(game_type.is_textual());
+
+        let game_type = GameType::from_static(b"").unwrap();
+        assert!(!game_type.is_numeric());
+        assert!(!game_type.is_textual());
+    }
+
+    #[test]
+    fn test_protocol_range() {
+        let number = |n| ProtocolNumber::new(n).unwrap();
+        let range = ProtocolRange::new(number(67), number(71));
+        assert_eq!(range.min(), number(67));
+        assert_eq!(range.max(), number(71));
+        assert!(!range.is_empty());
+        assert!(range.contains(number(67)));
+        assert!(range.contains(number(68)));
+        assert!(range.contains(number(71)));
+        assert!(!range.contains(number(1)));
+        assert!(!range.contains(number(66)));
+        assert!(!range.contains(number(72)));
+
+        let single = ProtocolRange::from(number(3));
+        assert!(single.contains(number(3)));
+        assert!(!single.contains(number(4)));
+
+        let empty = ProtocolRange::new(number(71), number(67));
+        assert!(empty.is_empty());
+        assert!(!empty.contains(number(68)));
+    }
+
+    #[test]
+    fn test_getserversresponse_extend_from() {
+        let first: SocketAddrV4 = "192.0.2.1:27960".parse().unwrap();
+        let second: SocketAddrV4 = "198.51.100.2:27961".parse().unwrap();
+        let third: SocketAddrV4 = "203.0.113.3:27962".parse().unwrap();
+
+        let mut message = GetServersResponseMessage::new(vec![first], false);
+        message.extend_from(GetServersResponseMessage::new(vec![second], false));
+        assert_eq!(message.servers(), &[first, second]);
+        assert!(!message.eot());
+
+        let mut last = GetServersResponseMessage::new(vec![third], true);
+        last.set_trailer(b"\x01".to_vec());
+        message.extend_from(last);
+        assert_eq!(message.servers(), &[first, second, third]);
+        assert!(message.eot());
+        assert_eq!(message.trailer(), b"\x01");
+    }
+
+    #[test]
+    fn test_info_challenge() {
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
+            InfoValue::new(b"8".to_vec()).unwrap(),
+        );
+        info.insert(
+            InfoKey::new(b"challenge".to_vec()).unwrap(),
+            InfoValue::new(b"A_ch4Lleng3".to_vec()).unwrap(),
+        );
+        assert_eq!(
+            info.challenge(),
+            Ok(Some(Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_info_challenge_missing() {
+        let mut info = Info::new();
+        assert_eq!(info.challenge(), Ok(None));
+
+        info.insert(
+            InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
+            InfoValue::new(b"8".to_vec()).unwrap(),
+        );
+        assert_eq!(info.challenge(), Ok(None));
+    }
+
+    #[test]
+    fn test_info_challenge_invalid() {
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"challenge".to_vec()).unwrap(),
+            InfoValue::new(b"".to_vec()).unwrap(),
+        );
+        assert!(matches!(
+            info.challenge(),
+            Err(InvalidChallengeError::Empty(_))
+        ));
+    }
+
+    fn info_with(key: &[u8], value: &[u8]) -> Info {
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(key.to_vec()).unwrap(),
+            InfoValue::new(value.to_vec()).unwrap(),
+        );
+        info
+    }
+
+    #[test]
+    fn test_info_sv_maxclients() {
+        assert_eq!(
+            info_with(b"sv_maxclients", b"8").sv_maxclients(),
+            Ok(MaxClientsNumber::new(8).unwrap())
+        );
+        assert_eq!(
+            info_with(b"sv_maxclients", b"0").sv_maxclients(),
+            Err(InfoFieldError::Invalid("sv_maxclients"))
+        );
+        assert_eq!(
+            info_with(b"sv_maxclients", b"abc").sv_maxclients(),
+            Err(InfoFieldError::Invalid("sv_maxclients"))
+        );
+        assert_eq!(
+            info_with(b"sv_maxclients", b"+8").sv_maxclients(),
+            Err(InfoFieldError::Invalid("sv_maxclients"))
+        );
+        assert_eq!(
+            info_with(b"clients", b"8").sv_maxclients(),
+            Err(InfoFieldError::Missing("sv_maxclients"))
+        );
+    }
+
+    #[test]
+    fn test_info_protocol() {
+        assert_eq!(
+            info_with(b"protocol", b"68").protocol(),
+            Ok(ProtocolNumber::new(68).unwrap())
+        );
+        assert_eq!(
+            info_with(b"protocol", b"").protocol(),
+            Err(InfoFieldError::Invalid("protocol"))
+        );
+        assert_eq!(
+            info_with(b"protocol", b"4294967296").protocol(),
+            Err(InfoFieldError::Invalid("protocol"))
+        );
+        assert_eq!(
+            info_with(b"sv_maxclients", b"8").protocol(),
+            Err(InfoFieldError::Missing("protocol"))
+        );
+    }
+
+    #[test]
+    fn test_info_clients() {
+        assert_eq!(info_with(b"clients", b"0").clients(), Ok(0));
+        assert_eq!(info_with(b"clients", b"12").clients(), Ok(12));
+        assert_eq!(
+            info_with(b"clients", b"twelve").clients(),
+            Err(InfoFieldError::Invalid("clients"))
+        );
+        assert_eq!(
+            info_with(b"sv_maxclients", b"8").clients(),
+            Err(InfoFieldError::Missing("clients"))
+        );
+    }
+
+    #[test]
+    fn test_info_gamename_gametype() {
+        let mut info = info_with(b"gamename", b"Xonotic");
+        info.insert(
+            InfoKey::new(b"gametype".to_vec()).unwrap(),
+            InfoValue::new(b"ctf".to_vec()).unwrap(),
+        );
+        assert_eq!(
+            info.gamename(),
+            Ok(Some(GameName::new(b"Xonotic".to_vec()).unwrap()))
+        );
+        assert_eq!(
+            info.gametype(),
+            Ok(Some(GameType::new(b"ctf".to_vec()).unwrap()))
+        );
+
+        let (_, message) = crate::deserializer::inforesponse_message(
+            b"\xFF\xFF\xFF\xFFinfoResponse\n\\gamename\\Xonotic\\gametype\\ctf",
+        )
+        .unwrap();
+        assert_eq!(message.info().gamename(), info.gamename());
+        assert_eq!(message.info().gametype(), info.gametype());
+    }
+
+    #[test]
+    fn test_info_gamename_gametype_missing() {
+        let info = info_with(b"sv_maxclients", b"8");
+        assert_eq!(info.gamename(), Ok(None));
+        assert_eq!(info.gametype(), Ok(None));
+    }
+
+    #[test]
+    fn test_info_gamename_invalid() {
+        let info = info_with(b"gamename", b"My Game");
+        assert!(matches!(
+            info.gamename(),
+            Err(ProtocolError::InvalidGameName { .. })
+        ));
+    }
+
+    #[test]
+    fn test_info_get() {
+        let info = info_with(b"sv_maxclients", b"8");
+        let value = InfoValue::new(b"8".to_vec()).unwrap();
+        assert_eq!(
+            info.get(&InfoKey::new(b"sv_maxclients".to_vec()).unwrap()),
+            Some(&value)
+        );
+        assert_eq!(info.get_bytes(b"sv_maxclients"), Some(&value));
+
+        assert_eq!(info.get(&InfoKey::new(b"clients".to_vec()).unwrap()), None);
+        assert_eq!(info.get_bytes(b"clients"), None);
+        assert_eq!(info.get_bytes(b"sv_max"), None);
+    }
+
+    #[test]
+    fn test_info_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |info: &Info| {
+            let mut hasher = DefaultHasher::new();
+            info.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let mut a = info_with(b"sv_maxclients", b"8");
+        a.insert(
+            InfoKey::new(b"clients".to_vec()).unwrap(),
+            InfoValue::new(b"0".to_vec()).unwrap(),
+        );
+        let mut b = info_with(b"clients", b"0");
+        b.insert(
+            InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
+            InfoValue::new(b"8".to_vec()).unwrap(),
+        );
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(a);
+        assert!(set.contains(&b));
+
+        let c = info_with(b"sv_maxclients", b"8");
+        assert_ne!(hash(&c), hash(&b));
+    }
+
+    #[test]
+    fn test_game_type_constructors() {
+        let game_type = GameType::new(b"ctf".to_vec()).unwrap();
+        assert_eq!(GameType::new_borrowed(b"ctf"), Ok(game_type.clone()));
+        assert_eq!(GameType::from_static(b"ctf"), Ok(game_type));
+    }
+
+    #[test]
+    fn test_statusresponse_players() {
+        let message = StatusResponseMessage::new(
+            Info::new(),
+            vec![
+                Player::new(12, 48, b"player".to_vec()),
+                Player::new(3, 0, b"Sarge".to_vec()),
+                Player::new(-1, 120, b"^1red".to_vec()),
+                Player::new(0, 0, b"Grunt".to_vec()),
+            ],
+        );
+        assert_eq!(message.player_count(), 4);
+        assert_eq!(message.bot_count(), 2);
+        assert_eq!(message.players()[2].score(), -1);
+        assert_eq!(message.players()[2].name(), b"^1red");
+        assert!(!message.players()[0].is_bot());
+
+        let message = StatusResponseMessage::new(Info::new(), vec![]);
+        assert_eq!(message.player_count(), 0);
+        assert_eq!(message.bot_count(), 0);
+    }
+
+    #[test]
+    fn test_message_try_from() {
+        use std::convert::TryInto;
+
+        let message: Message = (&b"\xFF\xFF\xFF\xFFheartbeat DarkPlaces\x0A"[..])
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            message,
+            Message::Heartbeat(HeartbeatMessage::new(
+                ProtocolName::new(b"DarkPlaces".to_vec()).unwrap()
+            ))
+        );
+
+        let message: Message = (&b"\xFF\xFF\xFF\xFFgetinfo A_ch4Lleng3"[..])
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            message,
+            Message::GetInfo(GetInfoMessage::new(
+                Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap()
+            ))
+        );
+
+        let message: Message = (&b"\xFF\xFF\xFF\xFFinfoResponse\x0A\\sv_maxclients\\8"[..])
+            .try_into()
+            .unwrap();
+        assert_eq!(message.command(), Command::InfoResponse);
+
+        let message: Message = (&b"\xFF\xFF\xFF\xFFgetservers Nexuiz 3 empty full"[..])
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            message,
+            Message::GetServers(GetServersMessage::new(
+                Some(GameName::new(b"Nexuiz".to_vec()).unwrap()),
+                ProtocolNumber::new(3).unwrap(),
+                FilterOptions::new(None, true, true)
+            ))
+        );
+
+        let message: Message =
+            (&b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0"[..])
+                .try_into()
+                .unwrap();
+        assert_eq!(
+            message,
+            Message::GetServersResponse(GetServersResponseMessage::new(
+                vec!["1.2.3.4:2048".parse().unwrap()],
+                true
+            ))
+        );
+    }
+
+    #[test]
+    fn test_message_try_from_invalid() {
+        use std::convert::TryFrom;
+
+        assert_eq!(
+            Message::try_from(&b"hurz"[..]),
+            Err(ProtocolError::InvalidMessage)
+        );
+        assert_eq!(
+            Message::try_from(&b"\xFF\xFF\xFF\xFFgetservers 68 empty hurz"[..]),
+            Err(ProtocolError::TrailingBytes { len: 4 })
+        );
+    }
+
+    #[test]
+    fn test_infokey_into_protocolerror() {
+        fn info(key: &[u8], value: &[u8]) -> Result<Info> {
+            let mut info = Info::new();
+            info.insert(InfoKey::new(key)?, InfoValue::new(value)?);
+            Ok(info)
+        }
+
+        assert!(info(b"sv_maxclients", b"8").is_ok());
+        assert_eq!(
+            info(b"uh\\oh", b"8"),
+            Err(ProtocolError::InvalidInfoKey(
+                InvalidInfoKeyError::InvalidByte(InvalidByteError(2, b"uh\\oh".to_vec()))
+            ))
+        );
+        assert!(matches!(
+            info(b"sv_maxclients", b"uh\\oh"),
+            Err(ProtocolError::InvalidInfoValue(..))
+        ));
+    }
+
+    #[test]
+    fn test_message_downcast() {
+        use std::convert::TryFrom;
+
+        let message = Message::GetServersResponse(GetServersResponseMessage::new(vec![], true));
+        assert_eq!(
+            GetServersResponseMessage::try_from(message),
+            Ok(GetServersResponseMessage::new(vec![], true))
+        );
+
+        let message = Message::Print(PrintMessage::new(b"Invalid request\n".to_vec()));
+        assert_eq!(
+            GetServersResponseMessage::try_from(message),
+            Err(ProtocolError::UnexpectedMessage {
+                expected: Command::GetServersResponse,
+                found: Command::Print,
+            })
+        );
+    }
+
+    #[test]
+    fn test_info_with_capacity() {
+        let mut info = Info::with_capacity(8);
+        assert!(info.capacity() >= 8);
+        assert_eq!(info, Info::new());
+
+        // only a hint
+        for i in 0..16 {
+            info.insert(
+                InfoKey::new(format!("key{}", i)).unwrap(),
+                InfoValue::new(*b"value").unwrap(),
+            );
+        }
+        assert_eq!(info.iter().count(), 16);
+    }
+
+    #[test]
+    fn test_challenge_ct_eq() {
+        let challenges = [
+            Challenge::new(*b"A_ch4Lleng3").unwrap(),
+            Challenge::new(*b"A_ch4Lleng4").unwrap(),
+            Challenge::new(*b"A_ch4Lleng").unwrap(),
+            Challenge::new(*b"B_ch4Lleng3").unwrap(),
+        ];
+        for a in challenges.iter() {
+            for b in challenges.iter() {
+                assert_eq!(a.ct_eq(b), a == b);
+            }
+        }
+
+        let value = InfoValue::new(*b"A_ch4Lleng3").unwrap();
+        assert!(value.ct_eq(&InfoValue::new(*b"A_ch4Lleng3").unwrap()));
+        assert!(!value.ct_eq(&InfoValue::new(*b"").unwrap()));
+    }
+
+    #[test]
+    fn test_filter_ext_options_accepts_family() {
+        let v4: std::net::SocketAddr = "192.0.2.1:27960".parse().unwrap();
+        let v6: std::net::SocketAddr = "[2001:db8::1]:27960".parse().unwrap();
+
+        let options = FilterExtOptions::new(None, false, false, true, false);
+        assert!(options.accepts_family(&v4));
+        assert!(!options.accepts_family(&v6));
+
+        let options = FilterExtOptions::new(None, false, false, false, true);
+        assert!(!options.accepts_family(&v4));
+        assert!(options.accepts_family(&v6));
+
+        for (ipv4, ipv6) in [(true, true), (false, false)] {
+            let options = FilterExtOptions::new(None, false, false, ipv4, ipv6);
+            assert!(options.accepts_family(&v4));
+            assert!(options.accepts_family(&v6));
+        }
+    }
+
+    #[test]
+    fn test_message_display_getservers() {
+        let message = Message::GetServers(GetServersMessage::new(
+            Some(GameName::from_static(b"Nexuiz").unwrap()),
+            ProtocolNumber::new(3).unwrap(),
+            FilterOptions::new(None, true, false),
+        ));
+        assert_eq!(message.to_string(), "getservers(Nexuiz, proto=3, empty)");
+
+        let message = Message::GetServers(GetServersMessage::new(
+            None,
+            ProtocolNumber::new(68).unwrap(),
+            FilterOptions::new(Some("4".parse().unwrap()), true, true),
+        ));
+        assert_eq!(
+            message.to_string(),
+            "getservers(proto=68, gametype=4, empty, full)"
+        );
+    }
+
+    #[test]
+    fn test_message_display_getserversresponse() {
+        let servers = (1..=42)
+            .map(|i| std::net::SocketAddrV4::new([192, 0, 2, i].into(), 27960))
+            .collect();
+        let message = Message::GetServersResponse(GetServersResponseMessage::new(servers, true));
+        assert_eq!(message.to_string(), "getserversResponse(42 servers, eot)");
+
+        let message = Message::GetServersResponse(GetServersResponseMessage::new(vec![], false));
+        assert_eq!(message.to_string(), "getserversResponse(0 servers)");
+    }
+
+    #[test]
+    fn test_message_display_others() {
+        let message = Message::Heartbeat(HeartbeatMessage::new(ProtocolName::default()));
+        assert_eq!(message.to_string(), "heartbeat(DarkPlaces)");
+
+        let message = Message::GetServersExt(GetServersExtMessage::new(
+            GameName::from_static(b"Xonotic").unwrap(),
+            ProtocolNumber::new(3).unwrap(),
+            FilterExtOptions::new(None, false, false, false, true),
+        ));
+        assert_eq!(message.to_string(), "getserversExt(Xonotic, proto=3, ipv6)");
+
+        let message = Message::Print(PrintMessage::new(b"banned\n".to_vec()));
+        assert_eq!(message.to_string(), "print(\"banned\\n\")");
+    }
+
+    #[test]
+    fn test_info_validate_required() {
+        let mut info = Info::new();
+        for key in REQUIRED_INFO_KEYS.iter() {
+            info.insert(
+                InfoKey::new(key.as_bytes().to_vec()).unwrap(),
+                InfoValue::new(b"1".to_vec()).unwrap(),
+            );
+        }
+        info.insert(
+            InfoKey::new(b"hostname".to_vec()).unwrap(),
+            InfoValue::new(b"example".to_vec()).unwrap(),
+        );
+        assert_eq!(info.validate_required(), Ok(()));
+    }
+
+    #[test]
+    fn test_info_validate_required_missing() {
+        assert_eq!(
+            Info::new().validate_required(),
+            Err(REQUIRED_INFO_KEYS.to_vec())
+        );
+
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"protocol".to_vec()).unwrap(),
+            InfoValue::new(b"3".to_vec()).unwrap(),
+        );
+        info.insert(
+            InfoKey::new(b"challenge".to_vec()).unwrap(),
+            InfoValue::new(b"A_ch4Lleng3".to_vec()).unwrap(),
+        );
+        assert_eq!(
+            info.validate_required(),
+            Err(vec!["sv_maxclients", "clients", "gamename"])
+        );
+    }
+
+    fn paginate(count: u8) -> Vec<GetServersResponseMessage> {
+        let servers: Vec<std::net::SocketAddrV4> = (0..count)
+            .map(|i| std::net::SocketAddrV4::new([192, 0, 2, i].into(), 27960))
+            .collect();
+        let messages: Vec<_> = GetServersResponseMessage::paginate(&servers, 100).collect();
+        assert!(messages.iter().all(|m| m.serialized_len() <= 100));
+        let paginated: Vec<_> = messages
+            .iter()
+            .flat_map(|m| m.servers().iter().copied())
+            .collect();
+        assert_eq!(paginated, servers);
+        messages
+    }
+
+    #[test]
+    fn test_getserversresponse_paginate() {
+        // 100 bytes leave room for 10 servers per message
+        let messages = paginate(10);
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].eot());
+
+        let messages = paginate(11);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages.iter().map(|m| m.eot()).collect::<Vec<_>>(),
+            vec![false, true]
+        );
+
+        let messages = paginate(25);
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages
+                .iter()
+                .map(|m| m.servers().len())
+                .collect::<Vec<_>>(),
+            vec![10, 10, 5]
+        );
+        assert_eq!(
+            messages.iter().map(|m| m.eot()).collect::<Vec<_>>(),
+            vec![false, false, true]
+        );
+    }
+
+    #[test]
+    fn test_getserversresponse_paginate_empty() {
+        let messages: Vec<_> = GetServersResponseMessage::paginate(&[], 1400).collect();
+        assert_eq!(messages, vec![GetServersResponseMessage::new(vec![], true)]);
+    }
+
+    #[test]
+    fn test_info_to_string_map() {
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"hostname".to_vec()).unwrap(),
+            InfoValue::new("Ünicode".as_bytes().to_vec()).unwrap(),
+        );
+        info.insert(
+            InfoKey::new(b"clients".to_vec()).unwrap(),
+            InfoValue::new(b"0".to_vec()).unwrap(),
+        );
+
+        let expected: std::collections::HashMap<_, _> = vec![
+            ("hostname".to_string(), "Ünicode".to_string()),
+            ("clients".to_string(), "0".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(info.to_string_map(false), Ok(expected.clone()));
+        assert_eq!(info.to_string_map(true), Ok(expected));
+    }
+
+    #[test]
+    fn test_info_to_string_map_invalid_utf8() {
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"hostname".to_vec()).unwrap(),
+            InfoValue::new(b"caf\xE9".to_vec()).unwrap(),
+        );
+
+        assert!(info.to_string_map(false).is_err());
+        let map = info.to_string_map(true).unwrap();
+        assert_eq!(map["hostname"], "caf\u{FFFD}");
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_challenge_random() {
+        let challenge = Challenge::random(32).unwrap();
+        assert_eq!(challenge.len(), 32);
+        assert_eq!(
+            Challenge::new(challenge[..].to_vec()),
+            Ok(challenge.clone())
+        );
+        assert_ne!(Challenge::random(32).unwrap(), challenge);
+
+        assert!(matches!(
+            Challenge::random(0),
+            Err(InvalidChallengeError::Empty(..))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_challenge_from_seed() {
+        let challenge = Challenge::from_seed(42, 12).unwrap();
+        assert_eq!(challenge.len(), 12);
+        assert_eq!(Challenge::from_seed(42, 12).unwrap(), challenge);
+        assert_ne!(Challenge::from_seed(43, 12).unwrap(), challenge);
+        assert_eq!(
+            Challenge::new(challenge[..].to_vec()),
+            Ok(challenge.clone())
+        );
+
+        assert!(matches!(
+            Challenge::from_seed(42, 0),
+            Err(InvalidChallengeError::Empty(..))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_challenge_random_from() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let challenge = Challenge::random_from(&mut rng, b"xyz", 9).unwrap();
+        assert_eq!(challenge.len(), 9);
+        assert!(challenge[..].iter().all(|byte| b"xyz".contains(byte)));
+
+        assert!(matches!(
+            Challenge::random_from(&mut rng, b"", 9),
+            Err(InvalidChallengeError::Empty(..))
+        ));
+        assert!(matches!(
+            Challenge::random_from(&mut rng, b"\\", 9),
+            Err(InvalidChallengeError::InvalidByte(..))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_getinfo_new_random() {
+        let (getinfo, challenge) = GetInfoMessage::new_random(12).unwrap();
+        assert_eq!(getinfo.challenge(), &challenge);
+        assert_eq!(challenge.len(), 12);
+    }
+
+    #[test]
+    fn test_info_value_eq_bytes_and_str() {
+        let value = InfoValue::new(b"1".to_vec()).unwrap();
+        assert_eq!(value, b"1"[..]);
+        assert_eq!(value, &b"1"[..]);
+        assert_eq!(value, "1");
+        assert_ne!(value, b"0"[..]);
+        assert_ne!(value, "10");
+    }
+
+    #[test]
+    fn test_info_key_eq_bytes_and_str() {
+        let key = InfoKey::new(b"sv_maxclients".to_vec()).unwrap();
+        assert_eq!(key, &b"sv_maxclients"[..]);
+        assert_eq!(key, "sv_maxclients");
+        assert_ne!(key, "clients");
+    }
+
+    #[test]
+    fn test_command_roundtrip() {
+        for command in [
+            Command::Heartbeat,
+            Command::GetInfo,
+            Command::InfoResponse,
+            Command::GetServers,
+            Command::GetServersResponse,
+            Command::GetServersExt,
+            Command::GetServersExtResponse,
+            Command::GetStatus,
+            Command::StatusResponse,
+            Command::Print,
+        ] {
+            assert_eq!(Command::from_bytes(command.as_bytes()), Some(command));
+        }
+    }
+
+    #[test]
+    fn test_command_from_bytes_unknown() {
+        assert_eq!(Command::from_bytes(b""), None);
+        assert_eq!(Command::from_bytes(b"getserversresponse"), None);
+        assert_eq!(Command::from_bytes(b"getservers "), None);
+    }
+
+    fn inforesponse_with_gamename(gamename: Option<&[u8]>) -> InfoResponseMessage {
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
+            InfoValue::new(b"8".to_vec()).unwrap(),
+        );
+        if let Some(gamename) = gamename {
+            info.insert(
+                InfoKey::new(b"gamename".to_vec()).unwrap(),
+                InfoValue::new(gamename.to_vec()).unwrap(),
+            );
+        }
+        InfoResponseMessage::new(info)
+    }
+
+    #[test]
+    fn test_inforesponse_gamename_matches() {
+        let inforesponse = inforesponse_with_gamename(Some(b"Nexuiz"));
+        assert!(inforesponse.gamename_matches(Some(&GameName::from_static(b"Nexuiz").unwrap())));
+    }
+
+    #[test]
+    fn test_inforesponse_gamename_mismatch() {
+        let inforesponse = inforesponse_with_gamename(Some(b"Nexuiz"));
+        assert!(!inforesponse.gamename_matches(Some(&GameName::from_static(b"Xonotic").unwrap())));
+        assert!(!inforesponse.gamename_matches(Some(&GameName::from_static(b"nexuiz").unwrap())));
+
+        let inforesponse = inforesponse_with_gamename(None);
+        assert!(!inforesponse.gamename_matches(Some(&GameName::from_static(b"Nexuiz").unwrap())));
+    }
+
+    #[test]
+    fn test_inforesponse_gamename_no_filter() {
+        assert!(inforesponse_with_gamename(Some(b"Nexuiz")).gamename_matches(None));
+        assert!(inforesponse_with_gamename(None).gamename_matches(None));
+    }
+
+    #[test]
+    fn test_inforesponse_info_mut_remove() {
+        let mut info = Info::new();
+        info.insert(
+            InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
+            InfoValue::new(b"8".to_vec()).unwrap(),
+        );
+        info.insert(
+            InfoKey::new(b"g_password".to_vec()).unwrap(),
+            InfoValue::new(b"hunter2".to_vec()).unwrap(),
+        );
+        info.insert(
+            InfoKey::new(b"clients".to_vec()).unwrap(),
+            InfoValue::new(b"0".to_vec()).unwrap(),
+        );
+        let mut message = InfoResponseMessage::new(info);
+
+        let removed = message
+            .info_mut()
+            .remove(&InfoKey::new(b"g_password".to_vec()).unwrap());
+        assert_eq!(removed, Some(InfoValue::new(b"hunter2".to_vec()).unwrap()));
+
+        let mut expected = Info::new();
+        expected.insert(
+            InfoKey::new(b"sv_maxclients".to_vec()).unwrap(),
+            InfoValue::new(b"8".to_vec()).unwrap(),
+        );
+        expected.insert(
+            InfoKey::new(b"clients".to_vec()).unwrap(),
+            InfoValue::new(b"0".to_vec()).unwrap(),
+        );
+        assert_eq!(message.info(), &expected);
+        assert!(message
+            .info()
+            .iter()
+            .map(|(key, _)| &key[..])
+            .eq([&b"sv_maxclients"[..], &b"clients"[..]]));
+    }
+
+    #[test]
+    fn test_getserversresponse_push() {
+        let mut message = GetServersResponseMessage::new(vec![], false);
+        message.push(SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960));
+        message.push(SocketAddrV4::new(Ipv4Addr::new(198, 51, 100, 2), 27961));
+        let message = message.with_server(SocketAddrV4::new(Ipv4Addr::new(203, 0, 113, 3), 27962));
+        assert_eq!(
+            message.servers(),
+            &[
+                SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960),
+                SocketAddrV4::new(Ipv4Addr::new(198, 51, 100, 2), 27961),
+                SocketAddrV4::new(Ipv4Addr::new(203, 0, 113, 3), 27962),
+            ]
+        );
+        assert!(!message.eot());
+    }
+
+    #[test]
+    fn test_getserversresponse_set_eot() {
+        let mut message = GetServersResponseMessage::new(vec![], false);
+        message.set_eot(true);
+        assert!(message.eot());
+        message.set_eot(false);
+        assert!(!message.eot());
+    }
+
+    #[test]
+    fn test_getserversresponse_subnets() {
+        let message = GetServersResponseMessage::new(
+            vec![
+                SocketAddrV4::new(Ipv4Addr::new(198, 51, 100, 2), 27961),
+                SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960),
+                SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27961),
+                SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960),
+                SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 254), 27960),
+            ],
+            false,
+        );
+        assert_eq!(
+            message.subnets(),
+            vec![
+                (Ipv4Addr::new(192, 0, 2, 0), 24, 3),
+                (Ipv4Addr::new(198, 51, 100, 0), 24, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_getserversextresponse_subnets() {
+        let message = GetServersExtResponseMessage::new(
+            vec![
+                "192.0.2.1:27960".parse().unwrap(),
+                "[2001:db8::1]:27960".parse().unwrap(),
+                "[2001:db8::2]:27960".parse().unwrap(),
+            ],
+            true,
+        );
+        assert_eq!(
+            message.subnets(),
+            vec![
+                (Ipv4Addr::new(192, 0, 2, 0).into(), 24, 1),
+                ("2001:db8::".parse().unwrap(), 64, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_getserversresponse_as_ref() {
+        fn count<S: AsRef<[SocketAddrV4]>>(servers: S) -> usize {
+            servers.as_ref().len()
+        }
+
+        let message = GetServersResponseMessage::new(
+            vec![SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960)],
+            true,
+        );
+        assert_eq!(count(&message), 1);
+    }
+
+    #[test]
+    fn test_getserversresponse_eq_servers() {
+        let servers = vec![
+            SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960),
+            SocketAddrV4::new(Ipv4Addr::new(198, 51, 100, 2), 27961),
+        ];
+
+        let message = GetServersResponseMessage::new(servers.clone(), false);
+        assert_eq!(message, servers[..]);
+        assert_eq!(message, servers);
+        assert_ne!(message, servers[..1]);
+
+        let message = GetServersResponseMessage::new(servers.clone(), true);
+        assert_eq!(message, servers);
+    }
+
+    #[test]
+    fn test_getserversextresponse_family_counts() {
+        let message = GetServersExtResponseMessage::new(
+            vec![
+                "192.0.2.1:27960".parse().unwrap(),
+                "[2001:db8::1]:27960".parse().unwrap(),
+                "[2001:db8::2]:27960".parse().unwrap(),
+            ],
+            true,
+        );
+        assert_eq!(message.ipv4_count(), 1);
+        assert_eq!(message.ipv6_count(), 2);
+
+        let message = GetServersExtResponseMessage::new(vec![], true);
+        assert_eq!(message.ipv4_count(), 0);
+        assert_eq!(message.ipv6_count(), 0);
+    }
+
+    #[test]
+    fn test_diff_servers() {
+        let server = |i| std::net::SocketAddrV4::new(std::net::Ipv4Addr::new(192, 0, 2, i), 27960);
+        let message = |servers: &[u8]| -> GetServersResponseMessage {
+            servers.iter().copied().map(server).collect()
+        };
+
+        // overlapping
+        let (added, removed) = diff_servers(&message(&[1, 2, 3]), &message(&[4, 3, 2]));
+        assert_eq!(added, vec![server(4)]);
+        assert_eq!(removed, vec![server(1)]);
+
+        // disjoint
+        let (added, removed) = diff_servers(&message(&[1, 2]), &message(&[3, 4]));
+        assert_eq!(added, vec![server(3), server(4)]);
+        assert_eq!(removed, vec![server(1), server(2)]);
+
+        // equal
+        let (added, removed) = diff_servers(&message(&[1, 2]), &message(&[2, 1]));
+        assert!(added.is_empty());
+        assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn test_getserversresponse_retain() {
+        let mut message: GetServersResponseMessage = [
+            "192.0.2.1:27960",
+            "10.0.0.1:27960",
+            "198.51.100.2:27961",
+            "172.16.0.1:27960",
+            "192.168.1.1:27960",
+            "127.0.0.1:27960",
+        ]
+        .iter()
+        .map(|server| server.parse::<std::net::SocketAddrV4>().unwrap())
+        .collect();
+        message.retain(|server| !server.ip().is_private() && !server.ip().is_loopback());
+        assert_eq!(
+            message.servers(),
+            &[
+                "192.0.2.1:27960".parse().unwrap(),
+                "198.51.100.2:27961".parse().unwrap()
+            ]
+        );
+        assert!(message.eot());
+
+        let mut message: GetServersExtResponseMessage =
+            ["10.0.0.1:27960", "[2001:db8::1]:27960", "[::1]:27960"]
+                .iter()
+                .map(|server| server.parse::<std::net::SocketAddr>().unwrap())
+                .collect();
+        message.retain(|server| !server.ip().is_loopback());
+        message.retain(|server| match server.ip() {
+            std::net::IpAddr::V4(ip) => !ip.is_private(),
+            std::net::IpAddr::V6(_) => true,
+        });
+        assert_eq!(message.servers(), &["[2001:db8::1]:27960".parse().unwrap()]);
+    }
+
+    #[test]
+    fn test_getserversresponse_into_servers() {
+        let servers: Vec<std::net::SocketAddrV4> = vec![
+            "192.0.2.1:27960".parse().unwrap(),
+            "198.51.100.2:27961".parse().unwrap(),
+        ];
+        let message = GetServersResponseMessage::new(servers.clone(), true);
+        assert_eq!(message.into_servers(), servers);
+
+        let servers: Vec<std::net::SocketAddr> = vec![
+            "192.0.2.1:27960".parse().unwrap(),
+            "[2001:db8::1]:27960".parse().unwrap(),
+        ];
+        let message = GetServersExtResponseMessage::new(servers.clone(), true);
+        assert_eq!(message.into_servers(), servers);
+    }
+
+    #[test]
+    fn test_getserversresponse_from_iter() {
+        let servers = vec![
+            SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960),
+            SocketAddrV4::new(Ipv4Addr::new(198, 51, 100, 2), 27961),
+        ];
+
+        let message = servers
+            .clone()
+            .into_iter()
+            .collect::<GetServersResponseMessage>();
+        assert_eq!(message, GetServersResponseMessage::new(servers, true));
+    }
+
+    #[test]
+    fn test_getserversextresponse_from_iter() {
+        let servers: Vec<std::net::SocketAddr> = vec![
+            "192.0.2.1:27960".parse().unwrap(),
+            "[2001:db8::1]:27960".parse().unwrap(),
+        ];
+
+        let message = servers
+            .clone()
+            .into_iter()
+            .collect::<GetServersExtResponseMessage>();
+        assert_eq!(message, GetServersExtResponseMessage::new(servers, true));
+    }
+
+    #[test]
+    fn test_getserversextresponse_as_ref() {
+        let message = GetServersExtResponseMessage::new(
+            vec![
+                "192.0.2.1:27960".parse().unwrap(),
+                "[2001:db8::1]:27960".parse().unwrap(),
+            ],
+            true,
+        );
+        let servers: &[std::net::SocketAddr] = message.as_ref();
+        assert_eq!(servers, message.servers());
+    }
+
+    #[test]
+    fn test_getserversresponse_builder_eot_only_final() {
+        let servers = vec![
+            SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 27960),
+            SocketAddrV4::new(Ipv4Addr::new(198, 51, 100, 2), 27961),
+        ];
+
+        let message = GetServersResponseMessageBuilder::new()
+            .servers(servers.clone())
+            .build()
+            .unwrap();
+        assert_eq!(
+            message,
+            GetServersResponseMessage::new(servers.clone(), false)
+        );
+
+        let message = GetServersResponseMessageBuilder::new()
+            .servers(servers.clone())
+            .build_final()
+            .unwrap();
+        assert_eq!(message, GetServersResponseMessage::new(servers, true));
+    }
+
+    #[test]
+    fn test_getserversresponse_builder_empty() {
+        assert_eq!(
+            GetServersResponseMessageBuilder::new().build(),
+            Err(EmptyError(()))
+        );
+        assert_eq!(
+            GetServersResponseMessageBuilder::new().build_final(),
+            Err(EmptyError(()))
+        );
+    }
+
+    #[test]
+    fn test_getserversresponse_builder_allow_empty() {
+        let message = GetServersResponseMessageBuilder::new()
+            .allow_empty()
+            .build_final()
+            .unwrap();
+        assert_eq!(message, GetServersResponseMessage::new(vec![], true));
+    }
+}
@@ -1,18 +1,21 @@
 //! serializer for messages
 
 use crate::messages::{
-    Challenge, FilterOptions, GameName, GameType, GetInfoMessage, GetServersMessage,
-    GetServersResponseMessage, HeartbeatMessage, Info, InfoKey, InfoResponseMessage, InfoValue,
-    ProtocolName, ProtocolNumber,
+    Challenge, Command, FilterExtOptions, FilterOptions, GameName, GameType, GetInfoMessage,
+    GetServersExtMessage, GetServersMessage, GetServersResponseMessage, HeartbeatMessage, Info,
+    InfoKey, InfoResponseMessage, InfoValue, Message, PrintMessage, ProtocolName, ProtocolNumber,
 };
-use cookie_factory::bytes::{be_u16, be_u8};
+use cookie_factory::bytes::be_u16;
 use cookie_factory::combinator::{cond, slice, string};
 use cookie_factory::multi::many_ref;
 use cookie_factory::sequence::tuple;
 use cookie_factory::{SerializeFn, WriteContext};
 use std::io::Write;
 
-fn gen_message_prefix<W: Write>() -> impl SerializeFn<W> {
+/// Serializer for the `\xFF\xFF\xFF\xFF` message prefix
+///
+/// Useful to compose custom messages.
+pub fn gen_message_prefix<W: Write>() -> impl SerializeFn<W> {
     slice(b"\xFF\xFF\xFF\xFF")
 }
 
@@ -27,7 +30,8 @@ pub fn gen_heartbeat_message<'a, 'b: 'a, W: Write + 'a>(
 ) -> impl SerializeFn<W> + 'a {
     tuple((
         gen_message_prefix(),
-        slice(b"heartbeat "),
+        slice(Command::Heartbeat.as_bytes()),
+        slice(b" "),
         gen_protocol_name(message.protocol_name()),
         slice(b"\n"),
     ))
@@ -42,11 +46,22 @@ pub fn gen_getinfo_message<'a, 'b: 'a, W: Write + 'a>(
 ) -> impl SerializeFn<W> + 'a {
     tuple((
         gen_message_prefix(),
-        slice(b"getinfo "),
+        slice(Command::GetInfo.as_bytes()),
+        slice(b" "),
         gen_challenge(message.challenge()),
     ))
 }
 
+/// Like [`gen_getinfo_message`], but appends the `trailer` byte after the challenge
+///
+/// Some game servers expect the challenge to be terminated, e.g. by `b'\n'` or `b' '`.
+pub fn gen_getinfo_message_with_trailer<'a, 'b: 'a, W: Write + 'a>(
+    message: &'b GetInfoMessage,
+    trailer: u8,
+) -> impl SerializeFn<W> + 'a {
+    move |out: WriteContext<W>| tuple((gen_getinfo_message(message), slice([trailer])))(out)
+}
+
 fn gen_infokey<'a, 'b: 'a, W: Write + 'a>(info_key: &'b InfoKey) -> impl SerializeFn<W> + 'a {
     slice(&info_key[..])
 }
@@ -74,11 +89,30 @@ pub fn gen_inforesponse_message<'a, 'b: 'a, W: Write + 'a>(
 ) -> impl SerializeFn<W> + 'a {
     tuple((
         gen_message_prefix(),
-        slice(b"infoResponse\x0A"),
+        slice(Command::InfoResponse.as_bytes()),
+        slice(b"\x0A"),
         gen_info(message.info()),
     ))
 }
 
+fn gen_info_sorted<'a, 'b: 'a, W: Write + 'a>(info: &'b Info) -> impl SerializeFn<W> + 'a {
+    many_ref(info.sorted_by_key(), gen_info_kv)
+}
+
+/// Like [`gen_inforesponse_message`], but with the `Info` key-value pairs sorted by key
+///
+/// Unlike the insertion order, this is the same for equal `Info`s, e.g. to cache or deduplicate the serialized messages.
+pub fn gen_inforesponse_message_sorted<'a, 'b: 'a, W: Write + 'a>(
+    message: &'b InfoResponseMessage,
+) -> impl SerializeFn<W> + 'a {
+    tuple((
+        gen_message_prefix(),
+        slice(Command::InfoResponse.as_bytes()),
+        slice(b"\x0A"),
+        gen_info_sorted(message.info()),
+    ))
+}
+
 fn gen_game_name<'a, 'b: 'a, W: Write + 'a>(game_name: &'b GameName) -> impl SerializeFn<W> + 'a {
     slice(&game_name[..])
 }
@@ -91,6 +125,7 @@ fn gen_protocol_number<W: Write>(protocol_number: ProtocolNumber) -> impl Serial
     string(protocol_number.to_string())
 }
 
+// canonical order, see `gen_getservers_message`
 fn gen_filter_options<'a, 'b: 'a, W: Write + 'a>(
     filter_options: &'b FilterOptions,
 ) -> impl SerializeFn<W> + 'a {
@@ -106,12 +141,18 @@ fn gen_filter_options<'a, 'b: 'a, W: Write + 'a>(
     ))
 }
 
+/// Serializer for a `getservers` message
+///
+/// The filter options are always written in the canonical order `gametype=… empty full`,
+/// regardless of the order they were parsed in.\
+/// This order is stable, so serialized messages can be compared byte by byte, e.g. in golden tests.
 pub fn gen_getservers_message<'a, 'b: 'a, W: Write + 'a>(
     message: &'b GetServersMessage,
 ) -> impl SerializeFn<W> + 'a {
     tuple((
         gen_message_prefix(),
-        slice(b"getservers "),
+        slice(Command::GetServers.as_bytes()),
+        slice(b" "),
         move |out: WriteContext<W>| match message.game_name() {
             Some(game_name) => tuple((gen_game_name(game_name), slice(b" ")))(out),
             None => Ok(out),
@@ -121,30 +162,110 @@ pub fn gen_getservers_message<'a, 'b: 'a, W: Write + 'a>(
     ))
 }
 
-fn gen_socketaddrv4<'a, 'b: 'a, W: Write + 'a>(
-    addr: &'b std::net::SocketAddrV4,
+// canonical order, like `gen_filter_options` followed by the address families
+fn gen_filter_ext_options<'a, 'b: 'a, W: Write + 'a>(
+    filter_options: &'b FilterExtOptions,
+) -> impl SerializeFn<W> + 'a {
+    tuple((
+        move |out: WriteContext<W>| match filter_options.gametype() {
+            Some(gametype) => {
+                tuple((slice(b" "), slice(b"gametype="), gen_gametype(gametype)))(out)
+            }
+            None => Ok(out),
+        },
+        cond(filter_options.empty(), slice(b" empty")),
+        cond(filter_options.full(), slice(b" full")),
+        cond(filter_options.ipv4(), slice(b" ipv4")),
+        cond(filter_options.ipv6(), slice(b" ipv6")),
+    ))
+}
+
+/// Serializer for a `getserversExt` message
+///
+/// The filter options are always written in the canonical order `gametype=… empty full ipv4 ipv6`.
+pub fn gen_getserversext_message<'a, 'b: 'a, W: Write + 'a>(
+    message: &'b GetServersExtMessage,
 ) -> impl SerializeFn<W> + 'a {
-    let octets = addr.ip().octets();
-    move |out: WriteContext<W>| {
-        tuple((
-            slice(b"\\"),
-            many_ref(&octets[..], |&i| be_u8(i)),
-            be_u16(addr.port()),
-        ))(out)
+    tuple((
+        gen_message_prefix(),
+        slice(Command::GetServersExt.as_bytes()),
+        slice(b" "),
+        gen_game_name(message.game_name()),
+        slice(b" "),
+        gen_protocol_number(message.protocol_number()),
+        gen_filter_ext_options(message.filter_options()),
+    ))
+}
+
+/// Serializer for the raw octets of an IPv4 address
+///
+/// Writes the 4 octets in network byte order, i.e. `192.0.2.1` as `\xC0\x00\x02\x01`.
+pub fn gen_ipv4<W: Write>(ip: std::net::Ipv4Addr) -> impl SerializeFn<W> {
+    slice(ip.octets())
+}
+
+/// Serializer for the raw octets of an IPv6 address
+///
+/// Writes the 16 octets in network byte order, i.e. `2001:db8::1` as `\x20\x01\x0D\xB8\x00 … \x00\x01`.
+pub fn gen_ipv6<W: Write>(ip: std::net::Ipv6Addr) -> impl SerializeFn<W> {
+    slice(ip.octets())
+}
+
+/// Serializer for a server socket address as contained in a `getserversResponse` or `getserversExtResponse` message
+///
+/// Writes a `\` separator for IPv4 or `/` separator for IPv6,
+/// followed by the raw octets of the IP address and the port, all in network byte order.
+pub fn gen_socketaddr<W: Write>(addr: std::net::SocketAddr) -> impl SerializeFn<W> {
+    move |out: WriteContext<W>| match addr {
+        std::net::SocketAddr::V4(addr) => gen_socketaddrv4(&addr)(out),
+        std::net::SocketAddr::V6(addr) => {
+            tuple((slice(b"/"), gen_ipv6(*addr.ip()), be_u16(addr.port())))(out)
+        }
     }
 }
 
+fn gen_socketaddrv4<W: Write>(addr: &std::net::SocketAddrV4) -> impl SerializeFn<W> {
+    tuple((slice(b"\\"), gen_ipv4(*addr.ip()), be_u16(addr.port())))
+}
+
 pub fn gen_getserversresponse_message<'a, 'b: 'a, W: Write + 'a>(
     message: &'b GetServersResponseMessage,
 ) -> impl SerializeFn<W> + 'a {
     tuple((
         gen_message_prefix(),
-        slice(b"getserversResponse"),
+        slice(Command::GetServersResponse.as_bytes()),
         many_ref(message.servers(), gen_socketaddrv4),
         cond(message.eot(), slice(b"\\EOT\0\0\0")),
+        cond(message.eot(), slice(message.trailer())),
+    ))
+}
+
+pub fn gen_print_message<'a, 'b: 'a, W: Write + 'a>(
+    message: &'b PrintMessage,
+) -> impl SerializeFn<W> + 'a {
+    tuple((
+        gen_message_prefix(),
+        slice(Command::Print.as_bytes()),
+        slice(b"\n"),
+        slice(message.text()),
     ))
 }
 
+/// Serializer for any message
+///
+/// Dispatches to the serializer for the concrete message type, the counterpart to [`parse_message`](crate::deserializer::parse_message).
+pub fn gen_message<'a, 'b: 'a, W: Write + 'a>(message: &'b Message) -> impl SerializeFn<W> + 'a {
+    move |out: WriteContext<W>| match message {
+        Message::Heartbeat(message) => gen_heartbeat_message(message)(out),
+        Message::GetInfo(message) => gen_getinfo_message(message)(out),
+        Message::InfoResponse(message) => gen_inforesponse_message(message)(out),
+        Message::GetServers(message) => gen_getservers_message(message)(out),
+        Message::GetServersResponse(message) => gen_getserversresponse_message(message)(out),
+        Message::GetServersExt(message) => gen_getserversext_message(message)(out),
+        Message::Print(message) => gen_print_message(message)(out),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,6 +326,12 @@ mod tests {
         buffer: &b"\xFF\xFF\xFF\xFFgetinfo A_ch4Lleng3"[..]
     });
 
+    gen_message_test!(test_gen_getinfo_message_with_trailer {
+        message: GetInfoMessage::new(Challenge::new(b"A_ch4Lleng3".to_vec()).unwrap(),),
+        function: |message| gen_getinfo_message_with_trailer(message, b'\n'),
+        buffer: &b"\xFF\xFF\xFF\xFFgetinfo A_ch4Lleng3\n"[..]
+    });
+
     gen_message_test!(test_gen_inforesponse_message {
         message: InfoResponseMessage::new({
             let mut info = Info::new();
@@ -222,10 +349,42 @@ mod tests {
         buffer: &b"\xFF\xFF\xFF\xFFinfoResponse\x0A\\sv_maxclients\\8\\clients\\0"[..]
     });
 
+    #[test]
+    fn test_gen_inforesponse_message_sorted() {
+        let pairs = [
+            (&b"sv_maxclients"[..], &b"8"[..]),
+            (b"clients", b"0"),
+            (b"gamename", b"Xonotic"),
+        ];
+        let info = |order: &[usize]| {
+            let mut info = Info::new();
+            for &i in order {
+                let (key, value) = pairs[i];
+                info.insert(InfoKey::new(key).unwrap(), InfoValue::new(value).unwrap());
+            }
+            InfoResponseMessage::new(info)
+        };
+        let expected =
+            &b"\xFF\xFF\xFF\xFFinfoResponse\x0A\\clients\\0\\gamename\\Xonotic\\sv_maxclients\\8"[..];
+
+        for order in [[0, 1, 2], [2, 1, 0], [1, 0, 2]].iter() {
+            let message = info(order);
+            let buffer = gen_simple(gen_inforesponse_message_sorted(&message), Vec::new()).unwrap();
+            assert_eq!(buffer, expected);
+        }
+
+        // the default preserves the insertion order
+        let buffer = gen_simple(gen_inforesponse_message(&info(&[2, 1, 0])), Vec::new()).unwrap();
+        assert_eq!(
+            buffer,
+            &b"\xFF\xFF\xFF\xFFinfoResponse\x0A\\gamename\\Xonotic\\clients\\0\\sv_maxclients\\8"[..]
+        );
+    }
+
     gen_message_test!(test_gen_getservers_message_q3a {
         message: GetServersMessage::new(
             None,
-            67,
+            ProtocolNumber::new(67).unwrap(),
             FilterOptions::new(Some(GameType::new(b"0".to_vec()).unwrap()), true, true),
         ),
         function: gen_getservers_message,
@@ -233,7 +392,11 @@ mod tests {
     });
 
     gen_message_test!(test_gen_getservers_message_woet {
-        message: GetServersMessage::new(None, 84, FilterOptions::new(None, false, false),),
+        message: GetServersMessage::new(
+            None,
+            ProtocolNumber::new(84).unwrap(),
+            FilterOptions::new(None, false, false),
+        ),
         function: gen_getservers_message,
         buffer: &b"\xFF\xFF\xFF\xFFgetservers 84"[..]
     });
@@ -241,7 +404,7 @@ mod tests {
     gen_message_test!(test_gen_getservers_message_nexuiz {
         message: GetServersMessage::new(
             Some(GameName::new(b"Nexuiz".to_vec()).unwrap()),
-            3,
+            ProtocolNumber::new(3).unwrap(),
             FilterOptions::new(None, false, false),
         ),
         function: gen_getservers_message,
@@ -251,16 +414,225 @@ mod tests {
     gen_message_test!(test_gen_getservers_message_qfusion {
         message: GetServersMessage::new(
             Some(GameName::new(b"qfusion".to_vec()).unwrap()),
-            39,
+            ProtocolNumber::new(39).unwrap(),
             FilterOptions::new(None, false, true)
         ),
         function: gen_getservers_message,
         buffer: &b"\xFF\xFF\xFF\xFFgetservers qfusion 39 full"[..]
     });
 
+    gen_message_test!(test_gen_filter_options_order {
+        message: FilterOptions::new(Some(GameType::new(b"4".to_vec()).unwrap()), true, true),
+        function: gen_filter_options,
+        buffer: &b" gametype=4 empty full"[..]
+    });
+
+    gen_message_test!(test_gen_filter_options_order_gametype_full {
+        message: FilterOptions::new(Some(GameType::new(b"ctf".to_vec()).unwrap()), false, true),
+        function: gen_filter_options,
+        buffer: &b" gametype=ctf full"[..]
+    });
+
+    gen_message_test!(test_gen_filter_options_order_empty_full {
+        message: FilterOptions::new(None, true, true),
+        function: gen_filter_options,
+        buffer: &b" empty full"[..]
+    });
+
+    gen_message_test!(test_gen_getservers_message_for_protocol {
+        message: GetServersMessage::for_protocol(ProtocolNumber::new(68).unwrap()),
+        function: gen_getservers_message,
+        buffer: &b"\xFF\xFF\xFF\xFFgetservers 68"[..]
+    });
+
+    #[test]
+    fn test_gen_filter_options_canonical_order() {
+        let (_, message) = crate::deserializer::getservers_message(
+            b"\xFF\xFF\xFF\xFFgetservers 68 full empty gametype=4",
+        )
+        .unwrap();
+        let buffer = gen_simple(gen_getservers_message(&message), Vec::new()).unwrap();
+        assert_eq!(
+            buffer,
+            b"\xFF\xFF\xFF\xFFgetservers 68 gametype=4 empty full"
+        );
+    }
+
+    #[test]
+    fn test_getservers_gametype_with_equals_sign_roundtrip() {
+        let data = &b"\xFF\xFF\xFF\xFFgetservers 68 gametype=a=b empty"[..];
+        let (rest, message) = crate::deserializer::getservers_message(data).unwrap();
+        assert_eq!(rest, b"");
+        assert_eq!(
+            message.filter_options().gametype(),
+            Some(&GameType::new(b"a=b".to_vec()).unwrap())
+        );
+        let buffer = gen_simple(gen_getservers_message(&message), Vec::new()).unwrap();
+        assert_eq!(buffer, data);
+    }
+
+    gen_message_test!(test_gen_getserversext_message {
+        message: GetServersExtMessage::new(
+            GameName::new(b"Xonotic".to_vec()).unwrap(),
+            ProtocolNumber::new(3).unwrap(),
+            FilterExtOptions::new(
+                Some(GameType::new(b"ctf".to_vec()).unwrap()),
+                true,
+                false,
+                true,
+                true
+            ),
+        ),
+        function: gen_getserversext_message,
+        buffer: &b"\xFF\xFF\xFF\xFFgetserversExt Xonotic 3 gametype=ctf empty ipv4 ipv6"[..]
+    });
+
+    gen_message_test!(test_gen_getserversext_message_ipv6 {
+        message: GetServersExtMessage::new(
+            GameName::new(b"Xonotic".to_vec()).unwrap(),
+            ProtocolNumber::new(3).unwrap(),
+            FilterExtOptions::new(None, false, false, false, true),
+        ),
+        function: gen_getserversext_message,
+        buffer: &b"\xFF\xFF\xFF\xFFgetserversExt Xonotic 3 ipv6"[..]
+    });
+
+    #[test]
+    fn test_getserversext_roundtrip() {
+        let data = &b"\xFF\xFF\xFF\xFFgetserversExt Xonotic 3 empty full ipv4 ipv6"[..];
+        let (_, message) = crate::deserializer::getserversext_message(data).unwrap();
+        let buffer = gen_simple(gen_getserversext_message(&message), Vec::new()).unwrap();
+        assert_eq!(buffer, data);
+    }
+
+    macro_rules! gen_test {
+        (
+            $name:ident {
+                serializer: $serializer:expr,
+                buffer: $buf:expr
+            }
+        ) => {
+            #[test]
+            fn $name() {
+                let mut buffer = [0u8; 512];
+                let cursor = Cursor::new(&mut buffer[..]);
+                let cursor = gen_simple($serializer, cursor).unwrap();
+                let size = cursor.position() as usize;
+                let buffer = cursor.into_inner();
+
+                assert_eq!(&buffer[..size], $buf);
+            }
+        };
+    }
+
+    gen_test!(test_gen_message_prefix {
+        serializer: gen_message_prefix(),
+        buffer: &b"\xFF\xFF\xFF\xFF"[..]
+    });
+
+    gen_test!(test_gen_ipv4 {
+        serializer: gen_ipv4("192.0.2.1".parse().unwrap()),
+        buffer: &b"\xC0\x00\x02\x01"[..]
+    });
+
+    gen_test!(test_gen_ipv6 {
+        serializer: gen_ipv6("2001:db8::1".parse().unwrap()),
+        buffer: &b"\x20\x01\x0D\xB8\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x01"[..]
+    });
+
+    gen_test!(test_gen_socketaddr_v4 {
+        serializer: gen_socketaddr("192.0.2.1:27960".parse().unwrap()),
+        buffer: &b"\\\xC0\x00\x02\x01\x6D\x38"[..]
+    });
+
+    gen_test!(test_gen_socketaddr_v6 {
+        serializer: gen_socketaddr("[2001:db8::1]:27960".parse().unwrap()),
+        buffer: &b"/\x20\x01\x0D\xB8\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x01\x6D\x38"[..]
+    });
+
     gen_message_test!(test_gen_getserversresponse_message {
         message: GetServersResponseMessage::new(vec!["1.2.3.4:2048".parse().unwrap()], true),
         function: gen_getserversresponse_message,
         buffer: &b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0"[..]
     });
+
+    gen_message_test!(test_gen_getserversresponse_message_trailer {
+        message: {
+            let mut message = GetServersResponseMessage::new(vec![], true);
+            message.set_trailer(b"d0pk\x01".to_vec());
+            message
+        },
+        function: gen_getserversresponse_message,
+        buffer: &b"\xFF\xFF\xFF\xFFgetserversResponse\\EOT\0\0\0d0pk\x01"[..]
+    });
+
+    #[test]
+    fn test_getserversresponse_serialized_len() {
+        let len = |message: &GetServersResponseMessage| {
+            gen_simple(gen_getserversresponse_message(message), Vec::new())
+                .unwrap()
+                .len()
+        };
+
+        let message = GetServersResponseMessage::new(vec![], false);
+        assert_eq!(message.serialized_len(), len(&message));
+
+        let mut message = GetServersResponseMessage::new(
+            vec![
+                "192.0.2.1:27960".parse().unwrap(),
+                "192.0.2.2:27960".parse().unwrap(),
+            ],
+            true,
+        );
+        message.set_trailer(b"d0pk\x01".to_vec());
+        assert_eq!(message.serialized_len(), len(&message));
+    }
+
+    #[test]
+    fn test_gen_message_roundtrip() {
+        let messages = vec![
+            &b"\xFF\xFF\xFF\xFFheartbeat DarkPlaces\n"[..],
+            b"\xFF\xFF\xFF\xFFgetinfo A_ch4Lleng3",
+            b"\xFF\xFF\xFF\xFFinfoResponse\n\\sv_maxclients\\8\\clients\\0",
+            b"\xFF\xFF\xFF\xFFgetservers Nexuiz 3 gametype=ctf empty full",
+            b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0",
+            b"\xFF\xFF\xFF\xFFgetserversExt Xonotic 3 empty ipv6",
+            b"\xFF\xFF\xFF\xFFprint\nbanned\n",
+        ];
+        for data in messages {
+            let (_, message) = crate::deserializer::parse_message(data).unwrap();
+            let buffer = gen_simple(gen_message(&message), Vec::new()).unwrap();
+            assert_eq!(buffer, data, "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_wire_sizes() {
+        use crate::messages::{EOT_LEN, IPV4_ENTRY_LEN, IPV6_ENTRY_LEN, MESSAGE_PREFIX_LEN};
+
+        let len = |serializer| gen_simple(serializer, Vec::new()).unwrap().len();
+
+        assert_eq!(
+            gen_simple(gen_message_prefix(), Vec::new()).unwrap().len(),
+            MESSAGE_PREFIX_LEN
+        );
+        assert_eq!(
+            len(gen_socketaddr("192.0.2.1:27960".parse().unwrap())),
+            IPV4_ENTRY_LEN
+        );
+        assert_eq!(
+            len(gen_socketaddr("[2001:db8::1]:27960".parse().unwrap())),
+            IPV6_ENTRY_LEN
+        );
+
+        let message = GetServersResponseMessage::new(vec![], false);
+        let without_eot = gen_simple(gen_getserversresponse_message(&message), Vec::new())
+            .unwrap()
+            .len();
+        let message = GetServersResponseMessage::new(vec![], true);
+        let with_eot = gen_simple(gen_getserversresponse_message(&message), Vec::new())
+            .unwrap()
+            .len();
+        assert_eq!(with_eot - without_eot, EOT_LEN);
+    }
 }
@@ -0,0 +1,45 @@
+//! `dpmaster` protocol facade
+//!
+//! Re-exports the `dpmaster-proto` and `dpmaster-codec` crates, so a single dependency suffices.
+//!
+//! ## Features
+//!
+//! * `proto` (default): the [`messages`] as well as their [`deserializer`] and [`serializer`], see `dpmaster-proto`
+//! * `codec` (default): the runtime-agnostic `encode`/`decode` functions in `codec`, see `dpmaster-codec`
+//! * `client`: the Tokio `GameClientCodec` and game client helpers in `codec`
+#![cfg_attr(
+    feature = "codec",
+    doc = r#"
+# Examples
+
+```rust
+use dpmaster::messages::GetServersResponseMessage;
+
+let message = dpmaster::codec::decode(b"\xFF\xFF\xFF\xFFgetserversResponse\\EOT\0\0\0")?;
+assert_eq!(message, GetServersResponseMessage::new(vec![], true));
+# Ok::<(), std::io::Error>(())
+```
+"#
+)]
+#![cfg_attr(
+    all(feature = "proto", not(feature = "codec")),
+    doc = r#"
+# Examples
+
+```rust
+use dpmaster::messages::GetServersResponseMessage;
+
+let (_, message) = dpmaster::deserializer::getserversresponse_message::<()>(
+    b"\xFF\xFF\xFF\xFFgetserversResponse\\EOT\0\0\0",
+)
+.unwrap();
+assert_eq!(message, GetServersResponseMessage::new(vec![], true));
+```
+"#
+)]
+
+#[cfg(feature = "proto")]
+pub use dpmaster_proto::{deserializer, error, flow, messages, serializer, ProtocolError};
+
+#[cfg(feature = "codec")]
+pub use dpmaster_codec as codec;
@@ -0,0 +1,47 @@
+//! Smoke test for using the protocol through the facade crate only
+
+#[test]
+#[cfg(feature = "proto")]
+fn test_proto_parse() {
+    use dpmaster::deserializer::getservers_message;
+    use dpmaster::messages::{FilterOptions, GetServersMessage, ProtocolNumber};
+
+    let message = GetServersMessage::new(
+        Some("Nexuiz".parse().unwrap()),
+        ProtocolNumber::new(3).unwrap(),
+        FilterOptions::new(None, true, false),
+    );
+    assert_eq!(
+        getservers_message(b"\xFF\xFF\xFF\xFFgetservers Nexuiz 3 empty"),
+        Ok((&b""[..], message))
+    );
+}
+
+#[test]
+#[cfg(feature = "codec")]
+fn test_codec_encode_decode() {
+    use dpmaster::messages::{FilterOptions, GetServersMessage, ProtocolNumber};
+
+    let message = GetServersMessage::new(
+        None,
+        ProtocolNumber::new(68).unwrap(),
+        FilterOptions::new(None, false, false),
+    );
+    let mut buffer = Vec::new();
+    dpmaster::codec::encode(&message, &mut buffer).unwrap();
+    assert_eq!(buffer, b"\xFF\xFF\xFF\xFFgetservers 68");
+
+    let response = dpmaster::codec::decode(
+        b"\xFF\xFF\xFF\xFFgetserversResponse\\\x01\x02\x03\x04\x08\x00\\EOT\0\0\0",
+    )
+    .unwrap();
+    assert_eq!(response.servers(), &["1.2.3.4:2048".parse().unwrap()]);
+}
+
+#[test]
+#[cfg(feature = "client")]
+fn test_client_codec() {
+    use dpmaster::codec::GameClientCodec;
+
+    let _codec: GameClientCodec = GameClientCodec::new();
+}